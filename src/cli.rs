@@ -10,6 +10,17 @@ use piglog::LogMode;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    /// Output format (JSON is supported by: gen list, gen info, gen show, gen diff, gen latest, config check, history, profile list, profile current, is-unlocked)
+    pub output: OutputFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// Human readable text
+    Text,
+    /// Machine readable JSON
+    Json,
 }
 
 #[derive(Subcommand, Debug)]
@@ -30,6 +41,24 @@ pub enum Commands {
     ForceUnlock,
     /// Is Rebos unlocked? (Exit Status: (0 = Yes, 1 = No))
     IsUnlocked,
+    /// Show the journal of builds, rollbacks, deletions and other events
+    History {
+        #[clap(long)]
+        /// Only show events since a date or duration ago (Examples: 2024-01-31, 12h, 7d)
+        since: Option<String>,
+    },
+    /// Commit and build the user generation (Going back to the built generation if the build fails)
+    Switch(Switch),
+    /// Generation profile commands
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+    /// Export and import the generation store
+    Store {
+        #[command(subcommand)]
+        command: StoreCommands,
+    },
     /// Manager commands
     Managers {
         #[command(subcommand)]
@@ -42,6 +71,25 @@ pub enum Commands {
     },
 }
 
+impl Commands {
+    // Can the command print JSON? ('--output json')
+    pub fn supports_json(&self) -> bool {
+        match self {
+            Self::Gen { command } => matches!(command,
+                GenCommands::List
+                | GenCommands::Info { .. }
+                | GenCommands::Show(_)
+                | GenCommands::Diff { .. }
+                | GenCommands::Latest
+            ),
+            Self::Config { command } => matches!(command, ConfigCommands::Check | ConfigCommands::Schema { .. }),
+            Self::Profile { command } => matches!(command, ProfileCommands::List | ProfileCommands::Current),
+            Self::History { .. } | Self::IsUnlocked => true,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum APICommands {
     /// Use the Rebos log message system
@@ -74,18 +122,94 @@ pub enum ManagerCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommands {
+    /// List all profiles
+    List,
+    /// Print out the active profile
+    Current,
+    /// Make a profile the active profile
+    Switch {
+        /// Name of the profile (Example: work -> ~/.config/rebos/profiles/work.toml)
+        name: String,
+    },
+    /// Stop using a profile, and go back to gen.toml
+    Reset,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StoreCommands {
+    /// Bundle all generations into a tar archive
+    Export {
+        /// The archive to create (Example: rebos-store.tar)
+        file: String,
+        #[clap(long)]
+        /// Also bundle the user configuration directory
+        with_config: bool,
+    },
+    /// Restore generations from a tar archive made by 'store export'
+    Import {
+        /// The archive to import
+        file: String,
+        #[clap(long, conflicts_with = "replace")]
+        /// Add the generations after the existing ones (Renumbering them)
+        merge: bool,
+        #[clap(long)]
+        /// Delete the existing generations first
+        replace: bool,
+        #[clap(long)]
+        /// Also restore the user configuration directory (It must not exist yet)
+        with_config: bool,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ConfigCommands {
     /// Create a default Rebos configuration
     Init,
     /// Check for warnings and errors in the Rebos configuration
     Check,
+    /// Print the JSON Schema of a configuration file (For editor validation and completion)
+    Schema {
+        #[arg(value_enum, default_value_t = SchemaKind::Gen)]
+        /// The kind of file
+        kind: SchemaKind,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum ManagerSource {
+    /// The managers and hooks in the user configuration
+    Live,
+    /// The managers and hooks saved with the built generation (Falls back to live)
+    Snapshot,
+}
+
+#[derive(Parser, Debug)]
+pub struct Build {
+    #[arg(long, value_enum, default_value_t = ManagerSource::Snapshot)]
+    /// Which managers remove the items of the previously built generation
+    pub managers: ManagerSource,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum SchemaKind {
+    /// Generation files (gen.toml, machine files, imports, profiles)
+    Gen,
+    /// Manager files (managers/NAME.toml)
+    Manager,
+    /// The manager order file (manager_order.toml)
+    Order,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum GenCommands {
     /// Confirm your custom generation, and make it the 'current' generation
     Commit(Commit),
+    /// Add items to the user generation (Keeps comments and formatting)
+    Add(GenEdit),
+    /// Remove items from the user generation (Keeps comments and formatting)
+    Rm(GenEdit),
     /// List all system generations
     List,
     /// Delete duplicate generations
@@ -95,31 +219,72 @@ pub enum GenCommands {
     /// Automatically clean up the generations (clean-dups, align, etc...)
     TidyUp,
     /// Get information on the generation in the user's config
-    Info,
+    Info {
+        #[clap(long)]
+        /// Show which file every item came from
+        explain: bool,
+    },
     /// Print out what the latest system generation number is
     Latest,
     /// Delete older generations
     DeleteOld(GenDeleteOld),
     /// Delete a specific generation
     Delete(GenDelete),
-    /// The difference between 2 generations
+    /// Delete generations based on a retention policy (Falls back to the [gc] section of settings.toml)
+    Gc(GenGc),
+    /// Pin a generation (Pinned generations are never deleted)
+    Pin(GenPin),
+    /// Unpin a generation
+    Unpin(GenUnpin),
+    /// Print the commit message, metadata and items of a stored generation
+    Show(GenShow),
+    /// The difference between 2 generations (Defaults to what the next commit would change)
     Diff {
-        /// Generation to act as base
-        old: usize,
-        /// Generation to act as changes
-        new: usize,
+        #[clap(default_value = "current")]
+        /// Generation to act as base (A number, an ID, 'current', 'built', 'latest', 'user' or 'git:REV')
+        old: String,
+        #[clap(default_value = "user")]
+        /// Generation to act as changes (A number, an ID, 'current', 'built', 'latest', 'user' or 'git:REV')
+        new: String,
     },
     /// Command related to the 'current' generation
     Current {
         #[command(subcommand)]
         command: CurrentCommands,
     },
+    /// Find the first generation that broke something, by building generations in between
+    Bisect {
+        #[command(subcommand)]
+        command: BisectCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BisectCommands {
+    /// Start bisecting between a good and a bad generation
+    Start {
+        /// A generation that works (A number, an ID, 'current', 'built' or 'latest')
+        good: String,
+        /// A newer generation that is broken (A number, an ID, 'current', 'built' or 'latest')
+        bad: String,
+    },
+    /// Mark the built generation as good
+    Good,
+    /// Mark the built generation as bad
+    Bad,
+    /// Test every generation with a command (Exit code 0 means good, anything else means bad)
+    Run {
+        /// The command to run
+        script: String,
+    },
+    /// Stop bisecting, and build the originally built generation again
+    Reset,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum CurrentCommands {
     /// Build the 'current' generation (You can always roll back later)
-    Build,
+    Build(Build),
     /// Rollback to a previous generation (You still need to build after rolling back)
     Rollback(Rollback),
     /// Set the 'current' generation to the latest generation
@@ -147,8 +312,51 @@ impl CLIBoolean {
 
 #[derive(Parser, Debug)]
 pub struct GenDelete {
-    /// The generation to delete
-    pub generation: usize,
+    /// The generation to delete (A number, an ID, 'current', 'built' or 'latest')
+    pub generation: String,
+}
+
+#[derive(Parser, Debug)]
+pub struct GenShow {
+    /// Generation to show (A number, an ID, 'current', 'built' or 'latest')
+    pub generation: String,
+    #[clap(short, long, conflicts_with = "raw")]
+    /// Only show the items of this manager
+    pub manager: Option<String>,
+    #[clap(long)]
+    /// Print the stored generation file as is (Not with '--output json')
+    pub raw: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct GenGc {
+    #[clap(long)]
+    /// Keep the N newest generations
+    pub keep_last: Option<usize>,
+    #[clap(long)]
+    /// Keep generations committed within this long (Examples: 12h, 30d, 2w)
+    pub keep_within: Option<String>,
+    #[clap(long)]
+    /// Keep the N most recently built generations
+    pub keep_built_history: Option<usize>,
+    #[clap(long)]
+    /// Only list what would be deleted
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct GenPin {
+    /// The generation to pin (A number, an ID, 'current', 'built' or 'latest')
+    pub generation: String,
+    #[clap(long)]
+    /// Why the generation is pinned (Shows up in the list command)
+    pub note: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct GenUnpin {
+    /// The generation to unpin (A number, an ID, 'current', 'built' or 'latest')
+    pub generation: String,
 }
 
 #[derive(Parser, Debug)]
@@ -157,16 +365,68 @@ pub struct GenDeleteOld {
     pub how_many: usize,
 }
 
+#[derive(Parser, Debug)]
+pub struct GenEdit {
+    /// The manager of the items (Example: system)
+    pub manager: String,
+    #[arg(required = true)]
+    /// The items to add or remove
+    pub items: Vec<String>,
+    #[clap(long, conflicts_with = "machine")]
+    /// Edit an import instead (Example: desktop/kde -> ~/.config/rebos/imports/desktop/kde.toml)
+    pub import: Option<String>,
+    #[clap(long)]
+    /// Edit the machine specific generation file instead
+    pub machine: bool,
+    #[clap(long)]
+    /// Commit the user generation afterwards
+    pub commit: bool,
+    #[clap(long)]
+    /// Commit and build the user generation afterwards
+    pub build: bool,
+}
+
 #[derive(Parser, Debug)]
 pub struct Commit {
-    /// The commit message shows up in the list command
-    pub msg: String,
+    /// The commit message shows up in the list command (Generated from the changes if not given)
+    pub msg: Option<String>,
+    #[clap(short, long, conflicts_with = "msg")]
+    /// Write the commit message in $EDITOR
+    pub edit: bool,
+    #[clap(long)]
+    /// Commit even if nothing changed since the latest generation
+    pub allow_empty: bool,
+    #[clap(long)]
+    /// Build the generation after committing it
+    pub build: bool,
+    #[clap(long)]
+    /// Commit this profile instead of the active one (without switching to it)
+    pub profile: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct Switch {
+    #[clap(short, long)]
+    /// The commit message (Generated from the changes if not given)
+    pub msg: Option<String>,
+    #[clap(short, long, conflicts_with = "msg")]
+    /// Write the commit message in $EDITOR
+    pub edit: bool,
+    #[clap(long)]
+    /// Commit even if nothing changed since the latest generation
+    pub allow_empty: bool,
+    #[clap(long)]
+    /// Don't delete the new generation if the build fails
+    pub keep_failed: bool,
+    #[arg(long, value_enum, default_value_t = ManagerSource::Snapshot)]
+    /// Which managers remove the items of the previously built generation
+    pub managers: ManagerSource,
 }
 
 #[derive(Parser, Debug)]
 pub struct SetCurrent {
-    /// Generation to jump to
-    pub to: usize,
+    /// Generation to jump to (A number, an ID, 'current', 'built' or 'latest')
+    pub to: String,
 }
 
 #[derive(Parser, Debug)]
@@ -18,6 +18,7 @@ const DEFAULT_USER_GEN: &str =
 # --------------------- #
 
 # Other generation files to import. (Example: intensive_apps -> ~/.config/rebos/imports/intensive_apps.toml)
+# Imports can also be nested (desktop/kde), globs (dev/*), relative to this file (./local) or absolute (/srv/team-rebos/base).
 imports = [
     # \"intensive_apps\",
 ]
@@ -39,6 +40,22 @@ items = [
 items = [
     # \"bacon\",
 ]
+
+# Items to drop, even if an import brings them in. (Prefixing an item with '!' does the same.)
+# exclude = [
+#     \"cargo-watch\",
+# ]
+
+# Variables that can be used in items as '${name}'.
+# Machine files and 'REBOS_VAR_<name>' environment variables override them.
+# [vars]
+# kernel = \"lts\"
+
+# Items that are only used when every condition matches.
+# Conditions: distro, arch, hostname (glob), env (VAR or VAR=value), file, command
+# [[when]]
+# distro = \"arch\"
+# managers.system.items = [\"base-devel\"]
 ";
 
 const DEFAULT_PACKAGE_MANAGER_CONFIG: &str =
@@ -69,6 +86,16 @@ hook_name = \"system_packages\" # This is used in hooks. (Example: post_system_p
 
 [config]
 many_args = true
+
+# ------------------- #
+#    Inline hooks.    #
+# ------------------- #
+
+# Shell snippets that run alongside the hook files in '~/.config/rebos/hooks'.
+# Available: pre_add, post_add, pre_remove, post_remove, pre_sync, post_sync, pre_upgrade, post_upgrade
+
+[hooks]
+# pre_add = \"echo 'Installing system packages...'\"
 ";
 
 const DEFAULT_FLATPAK_MANAGER_CONFIG: &str =
@@ -84,6 +111,9 @@ hook_name = \"flatpaks\"
 
 [config]
 many_args = true
+
+[hooks]
+# pre_add = \"flatpak remote-add --if-not-exists flathub https://dl.flathub.org/repo/flathub.flatpakrepo\"
 ";
 
 const DEFAULT_CARGO_MANAGER_CONFIG: &str =
@@ -100,6 +130,26 @@ hook_name = \"crates\"
 many_args = true
 ";
 
+const DEFAULT_SETTINGS: &str =
+"# -------------------- #
+#    Rebos Settings    #
+# -------------------- #
+
+[commit]
+sort_items = false # Sort the items of every manager when committing.
+
+# Generation garbage collection. ('rebos gen gc')
+# The 'current', built and pinned generations are always kept.
+[gc]
+auto = false # Run garbage collection after every commit.
+# keep_last = 20 # Keep the 20 newest generations.
+# keep_within = \"30d\" # Keep generations committed within the last 30 days. (Units: s, m, h, d, w)
+# keep_built_history = 5 # Keep the 5 most recently built generations.
+
+[history]
+git = false # Record every commit in a git repository. ($XDG_STATE_HOME/rebos/history)
+";
+
 // This determinds if a function should
 // use the files from the user's config,
 // or from the base() directory.
@@ -125,6 +175,7 @@ pub fn init_user_config() -> Result<(), io::Error> {
         places::base_user(),
         places::base_user().add_str("machines").add_str(&system_hostname),
         places::base_user().add_str("imports"),
+        places::profiles(),
         places::base_user().add_str("hooks"),
         places::base_user().add_str("managers"),
     ];
@@ -143,10 +194,11 @@ pub fn init_user_config() -> Result<(), io::Error> {
 
     let files = vec![
         (DEFAULT_USER_GEN, config::config_for(Config::Generation, ConfigSide::User)),
-        (DEFAULT_USER_GEN, places::base_user().add_str("machines").add_str(&system_hostname).add_str("gen.toml")),
-        (DEFAULT_PACKAGE_MANAGER_CONFIG, places::base_user().add_str("managers/system.toml")),
-        (DEFAULT_FLATPAK_MANAGER_CONFIG, places::base_user().add_str("managers/flatpak.toml")),
-        (DEFAULT_CARGO_MANAGER_CONFIG, places::base_user().add_str("managers/cargo.toml")),
+        (DEFAULT_USER_GEN, crate::format::find(&places::base_user().add_str("machines").add_str(&system_hostname).add_str("gen"))),
+        (DEFAULT_PACKAGE_MANAGER_CONFIG, crate::format::find(&places::base_user().add_str("managers/system"))),
+        (DEFAULT_FLATPAK_MANAGER_CONFIG, crate::format::find(&places::base_user().add_str("managers/flatpak"))),
+        (DEFAULT_CARGO_MANAGER_CONFIG, crate::format::find(&places::base_user().add_str("managers/cargo"))),
+        (DEFAULT_SETTINGS, crate::settings::path()),
     ];
 
     for i in files.iter() {
@@ -168,7 +220,10 @@ pub fn init_user_config() -> Result<(), io::Error> {
 pub fn config_for(config: Config, side: ConfigSide) -> Path {
     return match config {
         Config::Generation => match side {
-            ConfigSide::User => places::base_user().add_str("gen.toml"),
+            ConfigSide::User => match crate::profile::active() {
+                Some(s) => crate::profile::path_for(&s),
+                None => crate::format::find(&places::base_user().add_str("gen")),
+            },
             ConfigSide::System => match generation::current_gen() {
                 Ok(o) => o,
                 Err(_e) => {
@@ -183,12 +238,18 @@ pub fn config_for(config: Config, side: ConfigSide) -> Path {
 
 pub trait ConfigInfoToMessage {
     fn msg(&self) -> String;
+    // Stable identifier for machine-readable output.
+    fn code(&self) -> &'static str;
 }
 
 pub enum ConfigError {
     InvalidManager(String, Vec<String>),
     MissingMachine,
+    MissingProfile(String),
+    ImportCycle(Vec<String>),
+    AmbiguousFormat(Vec<String>),
     FailedToDeserializeConfigGeneration,
+    FailedToDeserializeSettings,
 }
 
 impl ConfigInfoToMessage for ConfigError {
@@ -205,19 +266,44 @@ impl ConfigInfoToMessage for ConfigError {
                 message
             },
             Self::MissingMachine => format!("Missing configuration for machine! (Machine specific gen.toml...)"),
+            Self::MissingProfile(ref profile) => format!("Active profile '{profile}' does not exist! (profiles/{profile}.toml)"),
+            Self::ImportCycle(ref cycle) => format!("Import cycle detected! ({})", cycle.join(" -> ")),
+            Self::AmbiguousFormat(ref files) => format!("Files with the same name, but different formats! Only one can be used: {}", files.join(", ")),
             Self::FailedToDeserializeConfigGeneration => format!("Failed to deserialize config (user-side) generation!"),
+            Self::FailedToDeserializeSettings => format!("Failed to deserialize settings.toml!"),
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match *self {
+            Self::InvalidManager(_, _) => "invalid_manager",
+            Self::MissingMachine => "missing_machine",
+            Self::MissingProfile(_) => "missing_profile",
+            Self::ImportCycle(_) => "import_cycle",
+            Self::AmbiguousFormat(_) => "ambiguous_format",
+            Self::FailedToDeserializeConfigGeneration => "invalid_generation",
+            Self::FailedToDeserializeSettings => "invalid_settings",
         }
     }
 }
 
 pub enum ConfigWarning {
     UnusedHook(String),
+    UnmatchedExclusion(String, String),
 }
 
 impl ConfigInfoToMessage for ConfigWarning {
     fn msg(&self) -> String {
         match *self {
             Self::UnusedHook(ref hook) => format!("Hook '{hook}' is never used. (Doesn't match any manager 'hook_name' fields.)"),
+            Self::UnmatchedExclusion(ref man, ref item) => format!("Exclusion '{item}' in manager '{man}' does not match any item."),
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match *self {
+            Self::UnusedHook(_) => "unused_hook",
+            Self::UnmatchedExclusion(_, _) => "unmatched_exclusion",
         }
     }
 }
@@ -231,11 +317,26 @@ pub fn check_config() -> Result<Result<ConfigCheckMiscInfo, (Vec<ConfigError>, C
     let mut errors: Vec<ConfigError> = Vec::new();
     let mut warnings: Vec<ConfigWarning> = Vec::new();
 
-    match generation::gen(ConfigSide::User) {
-        Ok(_) => (),
+    match generation::gen_with_report(ConfigSide::User) {
+        Ok((_, report)) => {
+            // Check: Exclusions that don't match anything.
+            for (man, item) in report.unmatched_exclusions.into_iter() {
+                warnings.push(ConfigWarning::UnmatchedExclusion(man, item));
+            }
+
+            // Check: Imports that lead back to themselves.
+            for cycle in report.import_cycles.into_iter() {
+                errors.push(ConfigError::ImportCycle(cycle));
+            }
+        },
         Err(_) => errors.push(ConfigError::FailedToDeserializeConfigGeneration),
     };
 
+    match crate::settings::load() {
+        Ok(_) => (),
+        Err(_) => errors.push(ConfigError::FailedToDeserializeSettings),
+    };
+
     let managers = match crate::management::managers() {
         Ok(o) => o,
         Err(e) => {
@@ -282,10 +383,22 @@ pub fn check_config() -> Result<Result<ConfigCheckMiscInfo, (Vec<ConfigError>, C
     }
 
     // Check: Missing machine config.
-    if places::base_user().add_str(&format!("machines/{}", hostname)).add_str("gen.toml").exists() == false {
+    if crate::format::find(&places::base_user().add_str(&format!("machines/{}", hostname)).add_str("gen")).exists() == false {
         errors.push(ConfigError::MissingMachine);
     }
 
+    // Check: Files that only differ by extension. (Example: gen.toml and gen.yaml)
+    for conflict in crate::format::conflicts(&places::base_user()) {
+        errors.push(ConfigError::AmbiguousFormat(conflict));
+    }
+
+    // Check: Missing active profile.
+    if let Some(profile) = crate::profile::active() {
+        if crate::profile::exists(&profile) == false {
+            errors.push(ConfigError::MissingProfile(profile));
+        }
+    }
+
     // Check: Unused hooks.
     let stages_pre: [&str; 2] = ["pre", "post"];
     let stages_suf: [&str; 4] = ["add", "remove", "sync", "upgrade"];
@@ -309,8 +422,6 @@ pub fn check_config() -> Result<Result<ConfigCheckMiscInfo, (Vec<ConfigError>, C
         }
     }
 
-    let warnings_len = warnings.len();
-
     let misc_info = ConfigCheckMiscInfo {
         warnings,
     };
@@ -319,23 +430,44 @@ pub fn check_config() -> Result<Result<ConfigCheckMiscInfo, (Vec<ConfigError>, C
         return Ok(Err((errors, misc_info)));
     }
 
-    if warnings_len == 0 {
-        piglog::success!("Configuration has no errors or warnings! (^-^)");
-    }
-
     Ok(Ok(misc_info))
 }
 
 pub fn print_misc_info(misc_info: &ConfigCheckMiscInfo) {
+    if misc_info.warnings.len() == 0 {
+        piglog::success!("Configuration has no errors or warnings! (^-^)");
+    }
+
     for w in misc_info.warnings.iter() {
         piglog::warning!("{}", w.msg());
     }
 }
 
 pub fn print_errors_and_misc_info(errors: &[ConfigError], misc_info: &ConfigCheckMiscInfo) {
-    print_misc_info(misc_info);
+    for w in misc_info.warnings.iter() {
+        piglog::warning!("{}", w.msg());
+    }
 
     for e in errors {
         piglog::error!("{}", e.msg());
     }
 }
+
+// Print the JSON Schema of a configuration file.
+pub fn print_schema(kind: crate::cli::SchemaKind) -> Result<(), io::Error> {
+    let schema = match kind {
+        crate::cli::SchemaKind::Gen => schemars::schema_for!(generation::Generation),
+        crate::cli::SchemaKind::Manager => schemars::schema_for!(crate::management::Manager),
+        crate::cli::SchemaKind::Order => schemars::schema_for!(generation::ManagerOrder),
+    };
+
+    match serde_json::to_string_pretty(&schema) {
+        Ok(o) => println!("{o}"),
+        Err(e) => {
+            error!("Failed to serialize JSON Schema! ({e})");
+            return Err(custom_error("Failed to serialize JSON Schema!"));
+        },
+    };
+
+    Ok(())
+}
@@ -0,0 +1,352 @@
+#![allow(dead_code)]
+
+use std::io;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+use toml_edit::{ Array, Document, Item, Table, TableLike, Value };
+
+use crate::places;
+use crate::system;
+use crate::library::*;
+use crate::format::{ self, Format };
+use crate::config::{ Config, ConfigSide, config_for };
+use crate::generation;
+
+// Read a generation file as an editable TOML document. (Keeps comments and formatting.)
+fn read_document(path: &Path) -> Result<Document, io::Error> {
+    if Format::from_path(path) != Format::Toml {
+        error!("Only TOML files can be edited! ('{}')", path.to_string());
+        return Err(custom_error("Only TOML files can be edited!"));
+    }
+
+    if path.exists() == false {
+        return Ok(Document::new());
+    }
+
+    let gen_string = match file::read(path) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to read generation file! ('{}')", path.to_string());
+            return Err(e);
+        },
+    };
+
+    match gen_string.parse::<Document>() {
+        Ok(o) => Ok(o),
+        Err(e) => {
+            error!("Failed to parse generation file! ('{}')", path.to_string());
+            error!("{e}");
+
+            Err(custom_error("Failed to parse generation file!"))
+        },
+    }
+}
+
+fn write_document(path: &Path, document: &Document) -> Result<(), io::Error> {
+    if path.parent_path().exists() == false {
+        directory::create(&path.parent_path())?;
+    }
+
+    match file::write(&document.to_string(), path) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Failed to write generation file! ('{}')", path.to_string());
+            Err(e)
+        },
+    }
+}
+
+// Get the 'items' array of a manager, creating the '[managers.X]' table if it is missing.
+fn items_array<'a>(document: &'a mut Document, manager: &str) -> Result<&'a mut Array, io::Error> {
+    let managers = document.entry("managers").or_insert_with(|| {
+        let mut table = Table::new();
+        table.set_implicit(true);
+
+        Item::Table(table)
+    });
+
+    let managers = match managers.as_table_like_mut() {
+        Some(s) => s,
+        None => {
+            error!("Field 'managers' is not a table!");
+            return Err(custom_error("Field 'managers' is not a table!"));
+        },
+    };
+
+    if managers.contains_key(manager) == false {
+        let mut table = Table::new();
+        table.insert("items", toml_edit::value(Array::new()));
+
+        managers.insert(manager, Item::Table(table));
+    }
+
+    let man_table = match managers.get_mut(manager).and_then(|x| x.as_table_like_mut()) {
+        Some(s) => s,
+        None => {
+            error!("Field 'managers.{manager}' is not a table!");
+            return Err(custom_error("Manager field is not a table!"));
+        },
+    };
+
+    if man_table.contains_key("items") == false {
+        man_table.insert("items", toml_edit::value(Array::new()));
+    }
+
+    match man_table.get_mut("items").and_then(|x| x.as_array_mut()) {
+        Some(s) => Ok(s),
+        None => {
+            error!("Field 'managers.{manager}.items' is not an array!");
+            Err(custom_error("Items field is not an array!"))
+        },
+    }
+}
+
+// Is the array written with one item per line?
+fn is_multiline(array: &Array) -> bool {
+    if array.trailing().as_str().unwrap_or("").contains('\n') {
+        return true;
+    }
+
+    array.iter().any(|x| x.decor().prefix().and_then(|p| p.as_str()).unwrap_or("").contains('\n'))
+}
+
+// Add items to a manager in a generation file. Returns how many items were added.
+pub fn add_items(path: &Path, manager: &str, items: &[String]) -> Result<usize, io::Error> {
+    let mut document = read_document(path)?;
+
+    let array = items_array(&mut document, manager)?;
+
+    let multiline = is_multiline(array);
+
+    let mut added: usize = 0;
+
+    for i in items.iter() {
+        if array.iter().any(|x| x.as_str() == Some(i)) {
+            warning!("Item '{i}' is already in '{}'!", path.to_string());
+            continue;
+        }
+
+        let mut value = Value::from(i.as_str());
+
+        if multiline {
+            value.decor_mut().set_prefix("\n    ");
+            array.set_trailing_comma(true);
+        }
+
+        else if array.len() > 0 {
+            value.decor_mut().set_prefix(" ");
+        }
+
+        array.push_formatted(value);
+
+        added += 1;
+    }
+
+    if multiline && array.trailing().as_str().unwrap_or("").contains('\n') == false {
+        array.set_trailing("\n");
+    }
+
+    write_document(path, &document)?;
+
+    Ok(added)
+}
+
+// Remove items from an array. Returns how many items were removed.
+fn remove_from_array(array: &mut Array, items: &[String]) -> usize {
+    let mut removed: usize = 0;
+
+    for i in items.iter() {
+        loop {
+            let pos = match array.iter().position(|x| x.as_str() == Some(i)) {
+                Some(s) => s,
+                None => break,
+            };
+
+            let value = array.remove(pos);
+
+            // The new first item takes over the spacing (and comments) in front of the removed one.
+            if pos == 0 {
+                if let (Some(first), Some(prefix)) = (array.get_mut(0), value.decor().prefix()) {
+                    first.decor_mut().set_prefix(prefix.clone());
+                }
+            }
+
+            removed += 1;
+        }
+    }
+
+    removed
+}
+
+// Remove items from 'managers.X.items' of a table. (The document itself, or a '[[when]]' block.)
+fn remove_from_managers(table: &mut dyn TableLike, manager: &str, items: &[String]) -> usize {
+    let array = table.get_mut("managers")
+        .and_then(|x| x.as_table_like_mut())
+        .and_then(|x| x.get_mut(manager))
+        .and_then(|x| x.as_table_like_mut())
+        .and_then(|x| x.get_mut("items"))
+        .and_then(|x| x.as_array_mut());
+
+    match array {
+        Some(s) => remove_from_array(s, items),
+        None => 0,
+    }
+}
+
+// Remove items from a manager in a generation file, including its '[[when]]' blocks. Returns how many items were removed.
+pub fn remove_items(path: &Path, manager: &str, items: &[String]) -> Result<usize, io::Error> {
+    let mut document = read_document(path)?;
+
+    let mut removed = remove_from_managers(document.as_table_mut(), manager, items);
+
+    if let Some(when) = document.get_mut("when") {
+        if let Some(blocks) = when.as_array_of_tables_mut() {
+            for b in blocks.iter_mut() {
+                removed += remove_from_managers(b, manager, items);
+            }
+        }
+
+        else if let Some(blocks) = when.as_array_mut() {
+            for b in blocks.iter_mut().filter_map(|x| x.as_inline_table_mut()) {
+                removed += remove_from_managers(b, manager, items);
+            }
+        }
+    }
+
+    if removed > 0 {
+        write_document(path, &document)?;
+    }
+
+    Ok(removed)
+}
+
+// Add an import to a generation file. Returns false if it was already there.
+fn add_import(path: &Path, import: &str) -> Result<bool, io::Error> {
+    let mut document = read_document(path)?;
+
+    let imports = document.entry("imports").or_insert(toml_edit::value(Array::new()));
+
+    let array = match imports.as_array_mut() {
+        Some(s) => s,
+        None => {
+            error!("Field 'imports' is not an array!");
+            return Err(custom_error("Field 'imports' is not an array!"));
+        },
+    };
+
+    if array.iter().any(|x| x.as_str() == Some(import)) {
+        return Ok(false);
+    }
+
+    let mut value = Value::from(import);
+
+    if array.len() > 0 {
+        value.decor_mut().set_prefix(" ");
+    }
+
+    array.push_formatted(value);
+
+    write_document(path, &document)?;
+
+    Ok(true)
+}
+
+// Get the generation file to edit. (The active profile or gen.toml, the machine file, or an import.)
+pub fn target_path(import: Option<&str>, machine: bool) -> Result<Path, io::Error> {
+    if let Some(name) = import {
+        let stem = match name.starts_with('/') {
+            true => Path::new(name),
+            false => places::base_user().add_str("imports").add_str(name),
+        };
+
+        return Ok(match format::extension_of(name).is_some() {
+            true => stem,
+            false => format::find(&stem),
+        });
+    }
+
+    if machine {
+        return Ok(format::find(&places::base_user().add_str("machines").add_str(&system::hostname()?).add_str("gen")));
+    }
+
+    Ok(config_for(Config::Generation, ConfigSide::User))
+}
+
+// Add items to the user generation.
+pub fn gen_add(manager: &str, items: &[String], import: Option<&str>, machine: bool) -> Result<(), io::Error> {
+    if crate::management::managers()?.contains(&manager.to_string()) == false {
+        warning!("Manager '{manager}' does not exist! (Create it in ~/.config/rebos/managers before building.)");
+    }
+
+    let path = target_path(import, machine)?;
+
+    let new_file = path.exists() == false;
+
+    let added = add_items(&path, manager, items)?;
+
+    success!("Added {added} items to '{}'! ({manager})", path.to_string());
+
+    // A new import does nothing until something imports it.
+    if let (Some(name), true) = (import, new_file) {
+        let gen_path = config_for(Config::Generation, ConfigSide::User);
+
+        if Format::from_path(&gen_path) != Format::Toml {
+            warning!("Nothing imports '{}' yet! (Add '{name}' to the imports of '{}'.)", path.to_string(), gen_path.to_string());
+        }
+
+        else if add_import(&gen_path, name)? {
+            info!("Added import '{name}' to '{}'!", gen_path.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+// Remove items from the user generation. (Without a target, every file that declares the items is edited.)
+pub fn gen_rm(manager: &str, items: &[String], import: Option<&str>, machine: bool) -> Result<(), io::Error> {
+    let mut removed: usize = 0;
+
+    if import.is_some() || machine {
+        let path = target_path(import, machine)?;
+
+        removed += remove_items(&path, manager, items)?;
+
+        if removed == 0 {
+            error!("None of the items are declared in '{}'! ({manager})", path.to_string());
+            return Err(custom_error("No items were removed!"));
+        }
+    }
+
+    else {
+        let (_, report) = generation::gen_with_report(ConfigSide::User)?;
+
+        for i in items.iter() {
+            let paths = report.paths_of(manager, i);
+
+            if paths.len() == 0 {
+                warning!("Item '{i}' is not declared anywhere! ({manager})");
+                continue;
+            }
+
+            for (p, raw) in paths.iter() {
+                let count = remove_items(p, manager, &[raw.to_string()])?;
+
+                if count > 0 {
+                    info!("Removed '{raw}' from '{}'!", p.to_string());
+                }
+
+                removed += count;
+            }
+        }
+
+        if removed == 0 {
+            error!("No items were removed! ({manager})");
+            return Err(custom_error("No items were removed!"));
+        }
+    }
+
+    success!("Removed {removed} items! ({manager})");
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+#![allow(dead_code)]
+
+use serde::de::DeserializeOwned;
+use fspp::*;
+
+// Every extension a configuration file can have, in the order they are looked for.
+pub const EXTENSIONS: [&str; 4] = ["toml", "yaml", "yml", "json"];
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Format {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl Format {
+    // Pick the format based on the extension of the path. (TOML if it is unknown.)
+    pub fn from_path(path: &Path) -> Self {
+        match extension_of(&path.to_string()) {
+            Some("yaml") | Some("yml") => Self::Yaml,
+            Some("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+            Self::Json => "JSON",
+        }
+    }
+
+    pub fn deserialize<T: DeserializeOwned>(&self, string: &str) -> Result<T, String> {
+        match self {
+            Self::Toml => toml::from_str(string).map_err(|e| format!("{e:#?}")),
+            Self::Yaml => serde_yaml::from_str(string).map_err(|e| format!("{e}")),
+            Self::Json => serde_json::from_str(string).map_err(|e| format!("{e}")),
+        }
+    }
+}
+
+// Get the extension of a file name, if it is one of the supported extensions.
+pub fn extension_of(name: &str) -> Option<&'static str> {
+    EXTENSIONS.into_iter().find(|x| name.ends_with(&format!(".{x}")))
+}
+
+// Remove a supported extension from a file name. (Example: 'cargo.yaml' -> 'cargo')
+pub fn strip_extension(name: &str) -> String {
+    match extension_of(name) {
+        Some(s) => name[..(name.len() - s.len() - 1)].to_string(),
+        None => name.to_string(),
+    }
+}
+
+// Find the file for a path without an extension. (Example: 'managers/cargo' -> 'managers/cargo.yaml')
+// If none of them exist, the TOML path is returned.
+pub fn find(stem: &Path) -> Path {
+    for ext in EXTENSIONS {
+        let path = Path::new(&format!("{}.{ext}", stem.to_string()));
+
+        if path.exists() {
+            return path;
+        }
+    }
+
+    Path::new(&format!("{}.toml", stem.to_string()))
+}
+
+// Find files inside of a directory (recursively) that share the same name, but have different extensions.
+pub fn conflicts(dir: &Path) -> Vec<Vec<String>> {
+    let mut conflicts: Vec<Vec<String>> = Vec::new();
+
+    let entries = match std::fs::read_dir(dir.to_string()) {
+        Ok(o) => o,
+        Err(_) => return conflicts,
+    };
+
+    let mut files: Vec<String> = Vec::new();
+
+    for e in entries.filter_map(|x| x.ok()) {
+        let path = e.path();
+
+        if path.is_dir() {
+            conflicts.extend(self::conflicts(&Path::new(&path.to_string_lossy())));
+        }
+
+        else if extension_of(&path.to_string_lossy()).is_some() {
+            files.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    files.sort();
+
+    let mut stems: Vec<String> = files.iter().map(|x| strip_extension(x)).collect();
+    stems.dedup();
+
+    for s in stems {
+        let same: Vec<String> = files.iter().filter(|x| strip_extension(x) == s).map(|x| x.to_string()).collect();
+
+        if same.len() > 1 {
+            conflicts.push(same);
+        }
+    }
+
+    conflicts
+}
@@ -0,0 +1,250 @@
+use std::io;
+use serde::{ Serialize, Deserialize };
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+use colored::Colorize;
+
+use crate::places;
+use crate::library::{ self, * };
+use crate::cli::ManagerSource;
+
+// The state of a bisect in progress. (bisect.toml)
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BisectState {
+    /// The newest generation known to be good
+    pub good: usize,
+    /// The oldest generation known to be bad
+    pub bad: usize,
+    /// The generation that is built and waiting to be marked good or bad
+    pub testing: Option<usize>,
+    /// The generation that was built before bisecting (Restored at the end)
+    pub original_built: Option<usize>,
+    /// The generation that was 'current' before bisecting (Restored at the end)
+    pub original_current: Option<usize>,
+}
+
+fn path() -> Path {
+    places::base().add_str("bisect.toml")
+}
+
+// Is a bisect in progress?
+pub fn in_progress() -> bool {
+    path().exists()
+}
+
+fn read() -> Result<BisectState, io::Error> {
+    if in_progress() == false {
+        error!("No bisect in progress! (Start one with 'rebos gen bisect start GOOD BAD'.)");
+        return Err(custom_error("No bisect in progress!"));
+    }
+
+    let state_string = file::read(&path())?;
+
+    match toml::from_str(&state_string) {
+        Ok(o) => Ok(o),
+        Err(e) => {
+            error!("Failed to deserialize bisect state: {e} (Use 'rebos gen bisect reset' to give up.)");
+            Err(custom_error("Failed to deserialize bisect state!"))
+        },
+    }
+}
+
+fn write(state: &BisectState) -> Result<(), io::Error> {
+    let state_string = match toml::to_string(state) {
+        Ok(o) => o,
+        Err(_e) => {
+            error!("Failed to convert bisect state to string!");
+            return Err(custom_error("Failed to convert bisect state to string!"));
+        },
+    };
+
+    file::write(&state_string, &path())
+}
+
+// The generations between the known good and bad generations, that still need testing.
+fn candidates(state: &BisectState) -> Result<Vec<usize>, io::Error> {
+    let mut gen_nums: Vec<usize> = super::list_gen_nums()?.into_iter()
+        .filter(|x| *x > state.good && *x < state.bad)
+        .collect();
+
+    gen_nums.sort();
+
+    Ok(gen_nums)
+}
+
+// Start bisecting between a good and a bad generation.
+pub fn start(good: usize, bad: usize) -> Result<(), io::Error> {
+    crate::lock::abort_if_locked();
+
+    if in_progress() {
+        error!("A bisect is already in progress! (Use 'rebos gen bisect reset' to stop it.)");
+        return Err(custom_error("A bisect is already in progress!"));
+    }
+
+    if good >= bad {
+        error!("The good generation ({good}) must be older than the bad generation ({bad})!");
+        return Err(custom_error("Good generation must be older than the bad generation!"));
+    }
+
+    let state = BisectState {
+        good,
+        bad,
+        testing: None,
+        original_built: super::get_built_no_output().ok(),
+        original_current: super::get_current().ok(),
+    };
+
+    write(&state)?;
+
+    step(state)?;
+
+    Ok(())
+}
+
+// Mark the generation being tested as good or bad, and go to the next one. (Returns true when the bisect is finished.)
+pub fn mark(good: bool) -> Result<bool, io::Error> {
+    crate::lock::abort_if_locked();
+
+    let mut state = read()?;
+
+    let testing = match state.testing {
+        Some(s) => s,
+        None => {
+            error!("No generation is being tested!");
+            return Err(custom_error("No generation is being tested!"));
+        },
+    };
+
+    match good {
+        true => state.good = testing,
+        false => state.bad = testing,
+    };
+
+    state.testing = None;
+
+    write(&state)?;
+
+    step(state)
+}
+
+// Build the next generation to test, or finish if there are none left. (Returns true when the bisect is finished.)
+fn step(mut state: BisectState) -> Result<bool, io::Error> {
+    let candidates = candidates(&state)?;
+
+    if candidates.len() == 0 {
+        finish(&state)?;
+
+        return Ok(true);
+    }
+
+    let next = candidates[candidates.len() / 2];
+
+    // Each test halves the candidates.
+    let steps = (usize::BITS - candidates.len().leading_zeros()) as usize;
+
+    info!("Bisecting: {} generations left to test (Roughly {steps} steps)", candidates.len());
+
+    super::set_current(next, true)?;
+
+    state.testing = Some(next);
+
+    write(&state)?;
+
+    match super::build(ManagerSource::Snapshot) {
+        Ok(_) => (),
+        Err(e) => {
+            error!("Failed to build generation {next}! (Mark it with 'rebos gen bisect good/bad', or give up with 'rebos gen bisect reset'.)");
+            return Err(e);
+        },
+    };
+
+    success!("Built generation {next}! Test it, then run 'rebos gen bisect good' or 'rebos gen bisect bad'.");
+
+    Ok(false)
+}
+
+// Report the first bad generation, and go back to how things were before bisecting.
+fn finish(state: &BisectState) -> Result<(), io::Error> {
+    let commit = super::get_gen_commit_from_usize(state.bad).unwrap_or(String::from("<< COMMIT MESSAGE MISSING >>"));
+
+    println!("");
+
+    success!("First bad generation is: {} {}", state.bad, format!("({commit})").bright_black());
+
+    let good_gen = super::get_gen_from_usize(state.good)?;
+    let bad_gen = super::get_gen_from_usize(state.bad)?;
+
+    println!(
+        "\n{} {} {}",
+        state.good.to_string().bright_cyan().bold(),
+        "->".bright_black().bold(),
+        state.bad.to_string().bright_cyan().bold()
+    );
+
+    println!("");
+
+    library::print_history_gen(&library::history_gen(&good_gen, &bad_gen));
+
+    restore(state)
+}
+
+// Build the generation that was built before bisecting, and restore 'current'.
+fn restore(state: &BisectState) -> Result<(), io::Error> {
+    if let Some(b) = state.original_built {
+        if super::get_built_no_output().ok() != Some(b) {
+            info!("Restoring the originally built generation: {b}");
+
+            super::set_current(b, true)?;
+            super::build(ManagerSource::Snapshot)?;
+        }
+    }
+
+    if let Some(c) = state.original_current {
+        super::set_current(c, true)?;
+    }
+
+    fs_action::delete(&path())?;
+
+    Ok(())
+}
+
+// Test every generation with a script. (Exit code 0 means good, anything else means bad.)
+pub fn run(script: &str) -> Result<(), io::Error> {
+    crate::lock::abort_if_locked();
+
+    loop {
+        let state = read()?;
+
+        let testing = match state.testing {
+            Some(s) => s,
+            None => {
+                error!("No generation is being tested! (Did the last build fail?)");
+                return Err(custom_error("No generation is being tested!"));
+            },
+        };
+
+        info!("Running script on generation {testing}: {script}");
+
+        let good = library::run_command(script);
+
+        match good {
+            true => info!("Generation {testing} is good."),
+            false => info!("Generation {testing} is bad."),
+        };
+
+        if mark(good)? {
+            return Ok(());
+        }
+    }
+}
+
+// Stop bisecting, and go back to how things were before.
+pub fn reset() -> Result<(), io::Error> {
+    crate::lock::abort_if_locked();
+
+    let state = read()?;
+
+    restore(&state)
+}
@@ -0,0 +1,123 @@
+use std::io;
+use serde::{ Serialize, Deserialize };
+use schemars::JsonSchema;
+use hashbrown::HashMap;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::library::*;
+use crate::system;
+
+use super::{ Generation, GenerationUtils, Items };
+
+// A block of items that is only used when all of its predicates match the system.
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct When {
+    /// Distro ID from '/etc/os-release' (Example: 'arch')
+    pub distro: Option<String>,
+    /// CPU architecture (Example: 'x86_64')
+    pub arch: Option<String>,
+    /// Hostname glob (Example: 'work-*')
+    pub hostname: Option<String>,
+    /// Environment variable that has to be set ('VAR'), or set to a value ('VAR=value')
+    pub env: Option<String>,
+    /// File that has to exist
+    pub file: Option<String>,
+    /// Command that has to be in $PATH
+    pub command: Option<String>,
+    /// Imports to use when the block matches
+    pub imports: Vec<String>,
+    #[schemars(with = "std::collections::HashMap<String, Items>")]
+    /// Items to use when the block matches
+    pub managers: HashMap<String, Items>,
+}
+
+impl Default for When {
+    fn default() -> Self {
+        Self {
+            distro: None,
+            arch: None,
+            hostname: None,
+            env: None,
+            file: None,
+            command: None,
+            imports: Vec::new(),
+            managers: HashMap::new(),
+        }
+    }
+}
+
+impl When {
+    // Do all of the predicates match this system?
+    pub fn matches(&self) -> Result<bool, io::Error> {
+        if let Some(ref distro) = self.distro {
+            if system::distro_id()? != *distro {
+                return Ok(false);
+            }
+        }
+
+        if let Some(ref arch) = self.arch {
+            if system::arch() != *arch {
+                return Ok(false);
+            }
+        }
+
+        if let Some(ref hostname) = self.hostname {
+            let pattern = match glob::Pattern::new(hostname) {
+                Ok(o) => o,
+                Err(e) => {
+                    error!("Invalid hostname pattern in 'when' block! ('{hostname}': {e})");
+                    return Err(custom_error("Invalid hostname pattern!"));
+                },
+            };
+
+            if pattern.matches(&system::hostname()?) == false {
+                return Ok(false);
+            }
+        }
+
+        if let Some(ref env) = self.env {
+            let matched = match env.split_once('=') {
+                Some((name, value)) => std::env::var(name).ok().as_deref() == Some(value),
+                None => std::env::var_os(env).is_some(),
+            };
+
+            if matched == false {
+                return Ok(false);
+            }
+        }
+
+        if let Some(ref file) = self.file {
+            if Path::new(file).exists() == false {
+                return Ok(false);
+            }
+        }
+
+        if let Some(ref command) = self.command {
+            if system::command_exists(command) == false {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+// Merge every matching 'when' block into the generation, and drop the rest.
+pub fn evaluate(generation: &mut Generation) -> Result<(), io::Error> {
+    let blocks: Vec<When> = std::mem::take(&mut generation.when);
+
+    for block in blocks.into_iter() {
+        if block.matches()? {
+            generation.extend(Generation {
+                imports: block.imports,
+                managers: block.managers,
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use std::io;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+use colored::Colorize;
+
+use crate::places;
+use crate::library::*;
+use crate::settings::GcSettings;
+
+// Which generations to keep. (Generations that no rule keeps are deleted.)
+pub struct GcPolicy {
+    /// Keep the N newest generations
+    pub keep_last: Option<usize>,
+    /// Keep generations committed within this long
+    pub keep_within: Option<chrono::Duration>,
+    /// Keep the N most recently built generations
+    pub keep_built_history: Option<usize>,
+}
+
+impl GcPolicy {
+    pub fn new(keep_last: Option<usize>, keep_within: Option<&str>, keep_built_history: Option<usize>) -> Result<Self, io::Error> {
+        let keep_within = match keep_within {
+            Some(s) => match parse_duration(s) {
+                Some(d) => Some(d),
+                None => {
+                    error!("Invalid duration: '{s}' (Examples: 90m, 12h, 30d, 2w)");
+                    return Err(custom_error("Invalid duration!"));
+                },
+            },
+            None => None,
+        };
+
+        Ok(Self {
+            keep_last,
+            keep_within,
+            keep_built_history,
+        })
+    }
+
+    pub fn from_settings(settings: &GcSettings) -> Result<Self, io::Error> {
+        Self::new(settings.keep_last, settings.keep_within.as_deref(), settings.keep_built_history)
+    }
+
+    // Does the policy have any rules at all?
+    pub fn is_empty(&self) -> bool {
+        self.keep_last.is_none() && self.keep_within.is_none() && self.keep_built_history.is_none()
+    }
+}
+
+// Parse a duration like '90m', '12h', '30d' or '2w'. (None if it is invalid, or reaches back further than time itself.)
+pub fn parse_duration(duration: &str) -> Option<chrono::Duration> {
+    let duration = duration.trim();
+
+    // The unit is a character, not a byte. (Example: '3é' must not split inside 'é'.)
+    let (unit_start, unit) = duration.char_indices().last()?;
+    let number: u64 = duration[..unit_start].parse().ok()?;
+
+    let unit_seconds: u64 = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 60 * 60 * 24,
+        'w' => 60 * 60 * 24 * 7,
+        _ => return None,
+    };
+
+    let duration = chrono::Duration::from_std(std::time::Duration::from_secs(number.checked_mul(unit_seconds)?)).ok()?;
+
+    // Callers subtract the duration from the current time, which must not overflow.
+    chrono::Local::now().checked_sub_signed(duration)?;
+
+    Some(duration)
+}
+
+// When was the generation committed? (Falls back to the age of the commit file for older generations.)
+fn commit_time(generation: usize) -> Option<chrono::DateTime<chrono::Local>> {
+    if let Some(t) = super::meta::read(generation).and_then(|x| x.time()) {
+        return Some(t);
+    }
+
+    let modified = std::fs::metadata(places::gens().add_str(&generation.to_string()).add_str("commit").to_string()).ok()?.modified().ok()?;
+
+    Some(chrono::DateTime::<chrono::Local>::from(modified))
+}
+
+// When was the generation last built? (None if it was never built.)
+pub fn built_time(generation: usize) -> Option<chrono::DateTime<chrono::Local>> {
+    let timestamp = file::read(&places::gens().add_str(&generation.to_string()).add_str("last_built")).ok()?;
+
+    match chrono::DateTime::parse_from_rfc3339(timestamp.trim()) {
+        Ok(o) => Some(o.with_timezone(&chrono::Local)),
+        Err(_) => None,
+    }
+}
+
+// Remember that the generation was just built.
+pub fn mark_built(generation: usize) -> Result<(), io::Error> {
+    match file::write(&chrono::Local::now().to_rfc3339(), &places::gens().add_str(&generation.to_string()).add_str("last_built")) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Failed to create/write 'last_built' file!");
+            Err(e)
+        },
+    }
+}
+
+// Get the generations the policy would delete.
+pub fn plan(policy: &GcPolicy) -> Result<Vec<usize>, io::Error> {
+    let mut gen_nums = super::list_gen_nums()?;
+    gen_nums.sort();
+
+    let mut keep: Vec<usize> = Vec::new();
+
+    // The 'current', built and pinned generations are always kept.
+    for i in gen_nums.iter() {
+        if super::is_current(*i)? || (super::been_built() && super::is_built(*i)?) || super::is_pinned(*i) {
+            keep.push(*i);
+        }
+    }
+
+    if let Some(n) = policy.keep_last {
+        keep.extend(gen_nums.iter().rev().take(n));
+    }
+
+    if let Some(d) = policy.keep_within {
+        let oldest_allowed = chrono::Local::now() - d;
+
+        for i in gen_nums.iter() {
+            if let Some(t) = commit_time(*i) {
+                if t >= oldest_allowed {
+                    keep.push(*i);
+                }
+            }
+        }
+    }
+
+    if let Some(n) = policy.keep_built_history {
+        let mut built: Vec<(usize, chrono::DateTime<chrono::Local>)> = gen_nums.iter()
+            .filter_map(|x| built_time(*x).map(|t| (*x, t)))
+            .collect();
+
+        built.sort_by(|a, b| b.1.cmp(&a.1));
+
+        keep.extend(built.into_iter().take(n).map(|x| x.0));
+    }
+
+    Ok(gen_nums.into_iter().filter(|x| keep.contains(x) == false).collect())
+}
+
+// Delete every generation that the policy doesn't keep. Returns how many generations were (or would be) deleted.
+pub fn gc(policy: &GcPolicy, dry_run: bool, verbose: bool) -> Result<usize, io::Error> {
+    crate::lock::abort_if_locked();
+
+    if policy.is_empty() {
+        error!("No retention policy given! (Use the command line options, or the [gc] section of settings.toml.)");
+        return Err(custom_error("No retention policy given!"));
+    }
+
+    let to_delete = plan(policy)?;
+
+    if dry_run {
+        for i in to_delete.iter() {
+            let commit = super::get_gen_commit_from_usize(*i).unwrap_or(String::from("<< COMMIT MESSAGE MISSING >>"));
+
+            generic!("Would delete: {} {}", i, format!("({commit})").bright_black());
+        }
+
+        return Ok(to_delete.len());
+    }
+
+    for i in to_delete.iter() {
+        super::delete(*i, verbose)?;
+    }
+
+    Ok(to_delete.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("90s"), Some(chrono::Duration::seconds(90)));
+        assert_eq!(parse_duration("12h"), Some(chrono::Duration::hours(12)));
+        assert_eq!(parse_duration(" 30d "), Some(chrono::Duration::days(30)));
+        assert_eq!(parse_duration("2w"), Some(chrono::Duration::weeks(2)));
+    }
+
+    #[test]
+    fn parse_duration_invalid() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("   "), None);
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration("3"), None);
+        assert_eq!(parse_duration("-3d"), None);
+        assert_eq!(parse_duration("3y"), None);
+        assert_eq!(parse_duration("99999999999999999w"), None);
+    }
+
+    #[test]
+    fn parse_duration_non_ascii() {
+        assert_eq!(parse_duration("é"), None);
+        assert_eq!(parse_duration("3é"), None);
+        assert_eq!(parse_duration("é3d"), None);
+        assert_eq!(parse_duration("３d"), None);
+    }
+}
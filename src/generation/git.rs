@@ -0,0 +1,91 @@
+use std::io;
+use std::process::Command;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::places;
+use crate::library::*;
+use crate::system;
+use super::Generation;
+
+// The git repository that records every commit. (Only used if [history] git = true.)
+pub fn repo() -> Path {
+    places::base().add_str("history")
+}
+
+// Run git inside of the history repository, and get the output.
+fn git(args: &[&str]) -> Result<String, io::Error> {
+    if system::command_exists("git") == false {
+        error!("Git history is turned on, but 'git' was not found!");
+        return Err(custom_error("Git not found!"));
+    }
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo().to_string())
+        .args(args)
+        .output()?;
+
+    if output.status.success() == false {
+        error!("Git command failed: git {}", args.join(" "));
+        error!("{}", String::from_utf8_lossy(&output.stderr).trim());
+
+        return Err(custom_error("Git command failed!"));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// Create the history repository if it doesn't exist yet.
+fn init() -> Result<(), io::Error> {
+    if repo().add_str(".git").exists() {
+        return Ok(());
+    }
+
+    directory::create(&repo())?;
+
+    git(&["init", "-q"])?;
+    git(&["config", "user.name", "Rebos"])?;
+    git(&["config", "user.email", "rebos@localhost"])?;
+
+    info!("Created git history repository: {}", repo().to_string());
+
+    Ok(())
+}
+
+// Record a committed generation in the history repository.
+pub fn record(generation: usize, gen_string: &str, msg: &str) -> Result<(), io::Error> {
+    init()?;
+
+    file::write(gen_string, &repo().add_str("gen.toml"))?;
+    file::write(&generation.to_string(), &repo().add_str("generation"))?;
+
+    git(&["add", "-A"])?;
+    git(&["commit", "-q", "--allow-empty", "-m", &format!("Generation {generation}: {msg}")])?;
+
+    info!("Recorded generation {generation} in git history.");
+
+    Ok(())
+}
+
+// Get the generation and commit message stored at a git revision of the history repository.
+pub fn read(rev: &str) -> Result<(Generation, String), io::Error> {
+    if repo().add_str(".git").exists() == false {
+        error!("There is no git history yet! (Turn on [history] git in settings.toml, and commit.)");
+        return Err(custom_error("No git history!"));
+    }
+
+    let gen_string = git(&["show", &format!("{rev}:gen.toml")])?;
+    let msg = git(&["log", "-1", "--format=%s", rev])?;
+
+    let generation: Generation = match toml::from_str(&gen_string) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to deserialize generation at git revision '{rev}': {e}");
+            return Err(custom_error("Failed to deserialize generation!"));
+        },
+    };
+
+    Ok((generation, msg.trim().to_string()))
+}
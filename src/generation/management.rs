@@ -30,7 +30,13 @@ pub fn clean_dups(verbose: bool) -> Result<usize, io::Error> {
         let generation = super::get_gen_from_usize(i)?;
 
         if let Some(ref s) = comparison {
-            if generation == *s {
+            if generation == *s && super::is_pinned(i) {
+                if verbose {
+                    piglog::info!("Keeping generation {i}, because it is pinned!");
+                }
+            }
+
+            else if generation == *s {
                 let current = super::is_current(i)?;
                 let built = super::is_built(i)?;
 
@@ -106,8 +112,10 @@ pub fn align(verbose: bool) -> Result<usize, io::Error> {
             }
 
             match super::move_gen(i, new_number, verbose) {
-                Ok(_) => (),
+                Ok(_) => crate::journal::record(crate::journal::EventKind::Align, Some(i), Some(new_number), crate::journal::Outcome::Success, std::collections::BTreeMap::new()),
                 Err(e) => {
+                    crate::journal::record(crate::journal::EventKind::Align, Some(i), Some(new_number), crate::journal::Outcome::Failure, std::collections::BTreeMap::new());
+
                     piglog::fatal!("Failed to move generation {i} to {new_number}!");
 
                     return Err(e);
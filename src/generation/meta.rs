@@ -0,0 +1,141 @@
+use std::io;
+use serde::{ Serialize, Deserialize };
+use sha2::{ Sha256, Digest };
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::places;
+use crate::library::*;
+use crate::system;
+
+// Information about how and where a generation was made. (meta.toml)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields, default)]
+pub struct GenMeta {
+    /// Immutable ID of the generation (Stays the same when the generation is renumbered)
+    pub id: String,
+    /// When the generation was committed (RFC 3339)
+    pub timestamp: String,
+    pub hostname: String,
+    pub user: String,
+    /// Version of Rebos that committed the generation
+    pub version: String,
+    /// The generation that was 'current' before this one was committed
+    pub parent: Option<usize>,
+    /// The profile that produced the generation
+    pub profile: Option<String>,
+    /// SHA-256 hash of gen.toml
+    pub hash: String,
+}
+
+impl Default for GenMeta {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            timestamp: String::new(),
+            hostname: String::new(),
+            user: String::new(),
+            version: String::new(),
+            parent: None,
+            profile: None,
+            hash: String::new(),
+        }
+    }
+}
+
+impl GenMeta {
+    // Create the metadata for a generation that is being committed right now.
+    pub fn new(gen_string: &str, parent: Option<usize>, profile: Option<String>) -> Result<Self, io::Error> {
+        Ok(Self {
+            id: new_id(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            hostname: system::hostname()?,
+            user: username(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            parent,
+            profile,
+            hash: hash(gen_string),
+        })
+    }
+
+    // The time of the commit. (None if it is missing or invalid.)
+    pub fn time(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        match chrono::DateTime::parse_from_rfc3339(&self.timestamp) {
+            Ok(o) => Some(o.with_timezone(&chrono::Local)),
+            Err(_) => None,
+        }
+    }
+
+    // The first characters of the ID, for printing. (None for older generations that have no ID.)
+    pub fn short_id(&self) -> Option<String> {
+        match self.id.is_empty() {
+            true => None,
+            false => Some(self.id.chars().take(SHORT_ID_LEN).collect()),
+        }
+    }
+
+    // The date of the commit, formatted for printing.
+    pub fn date(&self) -> Option<String> {
+        self.time().map(|x| x.format("%Y-%m-%d %H:%M").to_string())
+    }
+}
+
+// Characters used in generation IDs. (Letters only, so an ID is never mistaken for a generation number.)
+const ID_ALPHABET: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm',
+    'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+// How long generation IDs are.
+const ID_LEN: usize = 16;
+
+// How many characters of the ID are printed.
+pub const SHORT_ID_LEN: usize = 7;
+
+// Create a new generation ID.
+pub fn new_id() -> String {
+    nanoid::nanoid!(ID_LEN, &ID_ALPHABET)
+}
+
+// Hash the contents of a gen.toml file.
+pub fn hash(gen_string: &str) -> String {
+    format!("{:x}", Sha256::digest(gen_string.as_bytes()))
+}
+
+fn path(gen_id: usize) -> Path {
+    places::gens().add_str(&gen_id.to_string()).add_str("meta.toml")
+}
+
+// Get the metadata of a generation. (None for older generations that don't have any.)
+pub fn read(gen_id: usize) -> Option<GenMeta> {
+    let meta_string = file::read(&path(gen_id)).ok()?;
+
+    match toml::from_str(&meta_string) {
+        Ok(o) => Some(o),
+        Err(_) => {
+            warning!("Failed to deserialize metadata of generation {gen_id}! (Ignoring it.)");
+
+            None
+        },
+    }
+}
+
+// Write the metadata of a generation.
+pub fn write(gen_id: usize, meta: &GenMeta) -> Result<(), io::Error> {
+    let meta_string = match toml::to_string(meta) {
+        Ok(o) => o,
+        Err(_e) => {
+            error!("Failed to convert generation metadata to string!");
+            return Err(custom_error("Failed to convert generation metadata to string!"));
+        },
+    };
+
+    match file::write(&meta_string, &path(gen_id)) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Failed to write metadata of generation {gen_id}!");
+            Err(e)
+        },
+    }
+}
@@ -1,10 +1,18 @@
 #![allow(dead_code)]
 
 pub mod management;
+pub mod condition;
+pub mod meta;
+pub mod gc;
+pub mod git;
+pub mod snapshot;
+pub mod bisect;
 
 use std::io;
 use colored::Colorize;
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
 use hashbrown::HashMap;
 use piglog::prelude::*;
 use piglog::*;
@@ -19,15 +27,23 @@ use crate::config::{Config, ConfigSide};
 use crate::config::config_for;
 use crate::system;
 use crate::lock::*;
+use crate::format::{ self, Format };
+use crate::journal;
+
+use condition::When;
+use meta::GenMeta;
 
 trait Migrate<T> {
     fn migrate(self) -> T;
 }
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields, default)]
+/// Order to run the managers in (manager_order.toml)
 pub struct ManagerOrder {
+    /// Managers to run first
     pub begin: Vec<String>,
+    /// Managers to run last
     pub end: Vec<String>,
 }
 
@@ -40,20 +56,32 @@ impl Default for ManagerOrder {
     }
 }
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields, default)]
 pub struct Items {
+    /// Items to be managed by the manager ('!item' excludes an item)
     pub items: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// Items to drop, even if an import brings them in
+    pub exclude: Vec<String>,
 }
 
 impl Default for Items {
     fn default() -> Self {
         Self {
             items: Vec::new(),
+            exclude: Vec::new(),
         }
     }
 }
 
+// Items are compared as sets, so order and duplicates don't matter.
+impl PartialEq for Items {
+    fn eq(&self, other: &Self) -> bool {
+        same_set(&self.items, &other.items) && same_set(&self.exclude, &other.exclude)
+    }
+}
+
 pub mod legacy_1 {
     use serde::{ Serialize, Deserialize };
 
@@ -88,9 +116,9 @@ pub mod legacy_1 {
     
             gen.imports = self.imports;
     
-            gen.managers.insert("system".to_string(), super::Items { items: self.pkgs });
-            gen.managers.insert("flatpak".to_string(), super::Items { items: self.flatpaks });
-            gen.managers.insert("cargo".to_string(), super::Items { items: self.crates });
+            gen.managers.insert("system".to_string(), super::Items { items: self.pkgs, ..Default::default() });
+            gen.managers.insert("flatpak".to_string(), super::Items { items: self.flatpaks, ..Default::default() });
+            gen.managers.insert("cargo".to_string(), super::Items { items: self.crates, ..Default::default() });
     
             gen
         }
@@ -144,6 +172,7 @@ pub mod legacy_2 {
             for (key, value) in self.pkg_managers.into_iter() {
                 gen.managers.insert(key, super::Items {
                     items: value.pkgs,
+                    ..Default::default()
                 });
             }
 
@@ -161,11 +190,22 @@ pub mod legacy_2 {
     }
 }
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields, default)]
+/// A Rebos generation file (gen.toml, machine files, imports and profiles)
 pub struct Generation {
+    /// Other generation files to import (Example: desktop/kde, dev/*, ./local, /srv/team-rebos/base)
     pub imports: Vec<String>,
+    #[schemars(with = "std::collections::HashMap<String, Items>")]
+    /// Items for every manager (Example: managers.system.items)
     pub managers: HashMap<String, Items>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    /// Blocks that are only used when all of their conditions match
+    pub when: Vec<When>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "std::collections::HashMap<String, String>")]
+    /// Variables that can be used in items as '${name}'
+    pub vars: HashMap<String, String>,
 }
 
 impl Default for Generation {
@@ -173,70 +213,486 @@ impl Default for Generation {
         Generation {
             imports: Vec::new(),
             managers: HashMap::new(),
+            when: Vec::new(),
+            vars: HashMap::new(),
         }
     }
 }
 
+// Generations are compared semantically. (A missing manager is the same as a manager with no items.)
+impl PartialEq for Generation {
+    fn eq(&self, other: &Self) -> bool {
+        if same_set(&self.imports, &other.imports) == false {
+            return false;
+        }
+
+        let empty = Items::default();
+
+        for i in self.managers.keys().chain(other.managers.keys()) {
+            if self.managers.get(i).unwrap_or(&empty) != other.managers.get(i).unwrap_or(&empty) {
+                return false;
+            }
+        }
+
+        self.when == other.when && self.vars == other.vars
+    }
+}
+
 impl GenerationUtils for Generation {
     fn extend(&mut self, other_gen: Generation) {
         self.imports.extend(other_gen.imports);
 
         for i in other_gen.managers.keys() {
             match self.managers.get_mut(i) {
-                Some(s) => s.items.extend(other_gen.managers.get(i).unwrap().items.clone()),
+                Some(s) => {
+                    s.items.extend(other_gen.managers.get(i).unwrap().items.clone());
+                    s.exclude.extend(other_gen.managers.get(i).unwrap().exclude.clone());
+                },
                 None => {
-                    self.managers.insert(i.to_string(), Items { items: Vec::new() });
+                    self.managers.insert(i.to_string(), Items::default());
                     self.managers.get_mut(i).unwrap().items.extend(other_gen.managers.get(i).unwrap().items.clone());
+                    self.managers.get_mut(i).unwrap().exclude.extend(other_gen.managers.get(i).unwrap().exclude.clone());
                 },
             };
         }
+
+        self.when.extend(other_gen.when);
+        self.vars.extend(other_gen.vars);
+    }
+
+    fn normalize(&mut self, sort: bool) {
+        self.imports = remove_array_duplicates(&self.imports);
+
+        for items in self.managers.values_mut() {
+            items.items = remove_array_duplicates(&items.items);
+            items.items.retain(|x| x.trim().is_empty() == false);
+
+            if sort {
+                items.items.sort();
+            }
+        }
+
+        self.managers.retain(|_, v| v.items.len() > 0);
+
+        if sort {
+            self.imports.sort();
+        }
+    }
+
+    fn interpolate_vars(&mut self) -> Result<(), io::Error> {
+        for (man, items) in self.managers.iter_mut() {
+            for i in items.items.iter_mut().chain(items.exclude.iter_mut()) {
+                *i = match interpolate(i, &self.vars) {
+                    Ok(o) => o,
+                    Err(var) => {
+                        error!("Unknown variable '{var}' in item '{i}'! (Manager: '{man}')");
+                        return Err(custom_error("Unknown variable!"));
+                    },
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_exclusions(&mut self) -> Vec<(String, String)> {
+        let mut unmatched: Vec<(String, String)> = Vec::new();
+
+        for (man, items) in self.managers.iter_mut() {
+            let mut exclusions: Vec<String> = std::mem::take(&mut items.exclude);
+
+            for i in items.items.iter() {
+                if let Some(s) = i.strip_prefix('!') {
+                    exclusions.push(s.to_string());
+                }
+            }
+
+            items.items.retain(|x| x.starts_with('!') == false);
+
+            for e in remove_array_duplicates(&exclusions).into_iter() {
+                let before = items.items.len();
+
+                items.items.retain(|x| *x != e);
+
+                if items.items.len() == before {
+                    unmatched.push((man.to_string(), e));
+                }
+            }
+        }
+
+        unmatched
     }
 }
 
 pub trait GenerationUtils {
     /// Extend all of the fields from one Generation object to another, another being the caller
     fn extend(&mut self, other_gen: Generation);
+    /// Remove excluded items ('exclude' lists and '!item' entries), returning the exclusions that matched nothing as (manager, item)
+    fn apply_exclusions(&mut self) -> Vec<(String, String)>;
+    /// Replace every '${var}' in the items with the value from 'vars'
+    fn interpolate_vars(&mut self) -> Result<(), io::Error>;
+    /// Remove duplicate and blank items, drop managers with no items, and optionally sort everything
+    fn normalize(&mut self, sort: bool);
+}
+
+// Replace every '${var}' in a string. ('$${' is a literal '${'.) Returns the name of the variable if it is unknown.
+pub fn interpolate(text: &str, vars: &HashMap<String, String>) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = text;
+
+    while let Some(pos) = rest.find("${") {
+        if rest[..pos].ends_with('$') {
+            result.push_str(&rest[..(pos - 1)]);
+            result.push_str("${");
+            rest = &rest[(pos + 2)..];
+
+            continue;
+        }
+
+        result.push_str(&rest[..pos]);
+
+        let after = &rest[(pos + 2)..];
+
+        let end = match after.find('}') {
+            Some(s) => s,
+            None => {
+                result.push_str(&rest[pos..]);
+                rest = "";
+
+                break;
+            },
+        };
+
+        let name = &after[..end];
+
+        match vars.get(name) {
+            Some(s) => result.push_str(s),
+            None => return Err(name.to_string()),
+        };
+
+        rest = &after[(end + 1)..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+// The prefix for environment variables that override generation variables. (Example: REBOS_VAR_kernel=lts)
+const VAR_ENV_PREFIX: &str = "REBOS_VAR_";
+
+// Where an item was declared.
+pub struct ItemSource {
+    pub manager: String,
+    pub item: String,
+    /// The item as written in the file (Before interpolating variables)
+    pub raw: String,
+    pub source: String,
+    pub path: Path,
+}
+
+// Extra information gathered while resolving a generation.
+pub struct GenReport {
+    /// Exclusions that did not match any item (manager, item)
+    pub unmatched_exclusions: Vec<(String, String)>,
+    /// Import chains that lead back to themselves (Example: [a, b, a])
+    pub import_cycles: Vec<Vec<String>>,
+    /// Every file that declared each item
+    pub provenance: Vec<ItemSource>,
+}
+
+impl GenReport {
+    // Get every file that declared an item, and how it is written there. (Matches the item before or after interpolating variables.)
+    pub fn paths_of(&self, manager: &str, item: &str) -> Vec<(Path, String)> {
+        let mut paths: Vec<(Path, String)> = Vec::new();
+
+        for i in self.provenance.iter().filter(|x| x.manager == manager && (x.item == item || x.raw == item)) {
+            if paths.iter().any(|(p, r)| p.to_string() == i.path.to_string() && *r == i.raw) == false {
+                paths.push((i.path.clone(), i.raw.to_string()));
+            }
+        }
+
+        paths
+    }
+
+    // Get the names of every file that declared an item.
+    pub fn sources_of(&self, manager: &str, item: &str) -> Vec<String> {
+        let sources: Vec<String> = self.provenance.iter()
+            .filter(|x| x.manager == manager && x.item == item)
+            .map(|x| x.source.to_string())
+            .collect();
+
+        remove_array_duplicates(&sources)
+    }
 }
 
 // Return generation structure for...
 pub fn gen(side: ConfigSide) -> Result<Generation, io::Error> {
-    let mut generation = match read_to_gen(&config_for(Config::Generation, side)) {
-        Ok(o) => o,
-        Err(e) => return Err(e),
+    let (generation, report) = gen_with_report(side)?;
+
+    if report.import_cycles.len() > 0 {
+        for c in report.import_cycles.iter() {
+            error!("Import cycle detected! ({})", c.join(" -> "));
+        }
+
+        return Err(custom_error("Import cycle detected!"));
+    }
+
+    Ok(generation)
+}
+
+// Return generation structure, along with information gathered while resolving it.
+pub fn gen_with_report(side: ConfigSide) -> Result<(Generation, GenReport), io::Error> {
+    let mut state = ImportState {
+        visited: Vec::new(),
+        stack: Vec::new(),
+        cycles: Vec::new(),
+        provenance: Vec::new(),
     };
 
+    let gen_path = config_for(Config::Generation, side);
+
+    let mut generation = read_to_gen_with_state(&gen_path, &mut state)?;
+
+    let imports = std::mem::take(&mut generation.imports);
+
     let system_hostname = match system::hostname() {
         Ok(o) => o,
         Err(e) => return Err(e),
     };
 
     if side == ConfigSide::User {
-        generation.extend(read_to_gen(&places::base_user().add_str("machines").add_str(&system_hostname).add_str("gen.toml"))?);
+        let machine_path = format::find(&places::base_user().add_str("machines").add_str(&system_hostname).add_str("gen"));
+
+        let mut machine_gen = read_to_gen_with_state(&machine_path, &mut state)?;
+
+        let machine_imports = std::mem::take(&mut machine_gen.imports);
+
+        generation.extend(machine_gen);
+
+        resolve_imports(&mut generation, &imports, &gen_path, &mut state)?;
+        resolve_imports(&mut generation, &machine_imports, &machine_path, &mut state)?;
     }
 
-    while generation.imports.len() > 0 {
-        let gen_imports = generation.imports.clone();
+    else {
+        resolve_imports(&mut generation, &imports, &gen_path, &mut state)?;
+    }
+
+    // System generations already contain concrete values, so only the user side gets interpolated.
+    if side == ConfigSide::User {
+        for (key, value) in std::env::vars() {
+            if let Some(s) = key.strip_prefix(VAR_ENV_PREFIX) {
+                generation.vars.insert(s.to_string(), value);
+            }
+        }
 
-        for i in gen_imports.iter() {
-            let i_gen = read_to_gen(&places::base_user().add_str("imports").add_str(&format!("{i}.toml")))?;
+        generation.interpolate_vars()?;
 
-            generation.extend(i_gen);
+        for i in state.provenance.iter_mut() {
+            if let Ok(o) = interpolate(&i.item, &generation.vars) {
+                i.item = o;
+            }
         }
+    }
+
+    // The system generation should only contain concrete values.
+    generation.vars.clear();
+
+    let unmatched_exclusions = generation.apply_exclusions();
+
+    // Only keep the sources of items that survived the exclusions.
+    let provenance: Vec<ItemSource> = state.provenance.into_iter()
+        .filter(|x| match generation.managers.get(&x.manager) {
+            Some(s) => s.items.contains(&x.item),
+            None => false,
+        })
+        .collect();
+
+    Ok((generation, GenReport {
+        unmatched_exclusions,
+        import_cycles: state.cycles,
+        provenance,
+    }))
+}
+
+// Keeps track of where the import resolution is.
+struct ImportState {
+    /// Every import that has already been read
+    visited: Vec<String>,
+    /// The chain of imports currently being resolved
+    stack: Vec<String>,
+    cycles: Vec<Vec<String>>,
+    provenance: Vec<ItemSource>,
+}
+
+// Read every import (and the imports of those imports) into the generation, reading each import only once.
+fn resolve_imports(generation: &mut Generation, imports: &[String], importer: &Path, state: &mut ImportState) -> Result<(), io::Error> {
+    for i in imports.iter() {
+        for path in import_paths(i, importer)? {
+            let key = source_name(&path);
+
+            if let Some(pos) = state.stack.iter().position(|x| *x == key) {
+                let mut cycle: Vec<String> = state.stack[pos..].to_vec();
+                cycle.push(key);
 
-        let after_gen_imports = generation.imports.clone();
+                state.cycles.push(cycle);
 
-        for i in 0..after_gen_imports.len() {
-            if gen_imports.contains(&after_gen_imports[i]) {
-                generation.imports[i] = String::new();
+                continue;
             }
+
+            if state.visited.contains(&key) {
+                continue;
+            }
+
+            state.visited.push(key.to_string());
+
+            let mut i_gen = read_to_gen_with_state(&path, state)?;
+
+            let i_imports = std::mem::take(&mut i_gen.imports);
+
+            // Imports only provide defaults for variables, so the importing files can override them.
+            for (key, value) in std::mem::take(&mut i_gen.vars).into_iter() {
+                generation.vars.entry(key).or_insert(value);
+            }
+
+            generation.extend(i_gen);
+
+            state.stack.push(key);
+            resolve_imports(generation, &i_imports, &path, state)?;
+            state.stack.pop();
         }
+    }
+
+    Ok(())
+}
+
+// Find the files that an import refers to.
+// 'desktop/kde'  -> imports/desktop/kde.toml (or .yaml, .yml, .json)
+// 'dev/*'        -> Every file matching imports/dev/*.toml (or .yaml, .yml, .json)
+// './local'      -> local.toml, next to the importing file
+// '/srv/x'       -> /srv/x.toml
+// 'extra.json'   -> imports/extra.json (Explicit extension.)
+fn import_paths(name: &str, importer: &Path) -> Result<Vec<Path>, io::Error> {
+    let explicit_extension = format::extension_of(name).is_some();
+
+    let full = if name.starts_with('/') {
+        std::path::PathBuf::from(name)
+    }
+
+    else if name.starts_with("./") || name.starts_with("../") {
+        let importer_path = std::path::PathBuf::from(importer.to_string());
+
+        match importer_path.parent() {
+            Some(s) => s.join(name),
+            None => std::path::PathBuf::from(name),
+        }
+    }
+
+    else {
+        std::path::PathBuf::from(places::base_user().add_str("imports").to_string()).join(name)
+    };
+
+    // Tidy up things like 'a/./b' or 'a/../b', so the same file always has the same name.
+    let full = {
+        let mut normalized = std::path::PathBuf::new();
+
+        for c in full.components() {
+            match c {
+                std::path::Component::CurDir => (),
+                std::path::Component::ParentDir => { normalized.pop(); },
+                _ => normalized.push(c),
+            };
+        }
+
+        normalized
+    };
 
-        generation.imports = generation.imports.into_iter().filter(|x| *x != String::new()).collect();
+    let full_string = full.to_string_lossy().to_string();
+
+    if name.contains(|c| c == '*' || c == '?' || c == '[') {
+        let patterns: Vec<String> = match explicit_extension {
+            true => vec![full_string.to_string()],
+            false => format::EXTENSIONS.iter().map(|x| format!("{full_string}.{x}")).collect(),
+        };
+
+        let mut paths: Vec<String> = Vec::new();
+
+        for p in patterns.iter() {
+            let entries = match glob::glob(p) {
+                Ok(o) => o,
+                Err(e) => {
+                    error!("Invalid import pattern '{name}' in '{}'! ({e})", importer.to_string());
+                    return Err(custom_error("Invalid import pattern!"));
+                },
+            };
+
+            paths.extend(entries
+                .filter_map(|x| x.ok())
+                .filter(|x| x.is_file())
+                .map(|x| x.to_string_lossy().to_string()));
+        }
+
+        paths.sort();
+
+        if paths.len() == 0 {
+            error!("Import pattern '{name}' in '{}' did not match any files! ({})", importer.to_string(), patterns[0]);
+            return Err(custom_error("Import pattern did not match any files!"));
+        }
+
+        return Ok(paths.into_iter().map(|x| Path::new(&x)).collect());
+    }
+
+    let path = match explicit_extension {
+        true => Path::new(&full_string),
+        false => format::find(&Path::new(&full_string)),
+    };
+
+    if std::path::Path::new(&path.to_string()).is_file() == false {
+        error!("Import '{name}' in '{}' does not exist! ({})", importer.to_string(), path.to_string());
+        return Err(custom_error("Import does not exist!"));
+    }
+
+    Ok(vec![path])
+}
+
+// Read a user-side generation file, evaluate its conditions, and remember where its items came from.
+fn read_to_gen_with_state(path: &Path, state: &mut ImportState) -> Result<Generation, io::Error> {
+    let mut generation = read_to_gen(path)?;
+
+    condition::evaluate(&mut generation)?;
+
+    let source = source_name(path);
+
+    for (man, items) in generation.managers.iter() {
+        for i in items.items.iter() {
+            if i.starts_with('!') {
+                continue;
+            }
+
+            state.provenance.push(ItemSource {
+                manager: man.to_string(),
+                item: i.to_string(),
+                raw: i.to_string(),
+                source: source.to_string(),
+                path: path.clone(),
+            });
+        }
     }
 
     Ok(generation)
 }
 
+// Shorten a path for printing. (Example: ~/.config/rebos/imports/desktop.toml -> imports/desktop.toml)
+fn source_name(path: &Path) -> String {
+    let full = path.to_string();
+    let base = format!("{}/", places::base_user().to_string());
+
+    match full.strip_prefix(&base) {
+        Some(s) => s.to_string(),
+        None => full,
+    }
+}
+
 macro_rules! deserialize_legacy {
     ($gen: ident, $string: expr, $gen_type: ty, $version: expr) => {
         let mut should_try = false;
@@ -268,18 +724,27 @@ macro_rules! deserialize_legacy {
     };
 }
 
-// Read a file and return a Generation object.
-fn read_to_gen(path: &Path) -> Result<Generation, io::Error> {
+// Read a file and return a Generation object. (Migrates legacy generations.)
+pub fn read_to_gen(path: &Path) -> Result<Generation, io::Error> {
+    let format = Format::from_path(path);
+
     let gen_string = match file::read(path) {
         Ok(o) => o,
         Err(e) => {
-            error!("Failed to read generation TOML file!");
+            error!("Failed to read generation {} file! ('{}')", format.name(), path.to_string());
             return Err(e);
         },
     };
 
-    Ok(match toml::from_str(&gen_string) {
+    Ok(match format.deserialize(&gen_string) {
         Ok(o) => o,
+        Err(e) if format != Format::Toml => {
+            error!("Failed to deserialize generation {} file!", format.name());
+            error!("{e}");
+            error!("Path: '{}'", path.to_string());
+
+            return Err(custom_error("Failed to deserialize generation!"));
+        },
         Err(e) => {
             warning!("Failed to deserialize generation, attempting legacy modes... ('{}')", path.to_string());
 
@@ -294,7 +759,7 @@ fn read_to_gen(path: &Path) -> Result<Generation, io::Error> {
                         Ok(o) => o,
                         Err(_) => {
                             error!("Failed to deserialize in legacy modes! Regular deserialization error:");
-                            error!("{e:#?}");
+                            error!("{e}");
                             error!("Path: '{}'", path.to_string());
 
                             return Err(custom_error("Failed to deserialize generation!"));
@@ -328,6 +793,75 @@ pub fn get_gen_commit_from_usize(gen_id: usize) -> Result<String, io::Error> {
     return Ok(gen_commit);
 }
 
+// Get the generation number a reference points to. (A number, an ID prefix, 'current', 'built' or 'latest'.)
+pub fn resolve_ref(reference: &str) -> Result<usize, io::Error> {
+    let generation = match reference {
+        "current" => get_current()?,
+        "built" => get_built()?,
+        "latest" => latest_number()?,
+        _ => match reference.parse::<usize>() {
+            Ok(o) => o,
+            Err(_) => resolve_id(reference)?,
+        },
+    };
+
+    if gen_exists(generation) == false {
+        error!("Generation {generation} does not exist!");
+        return Err(custom_error("Generation does not exist!"));
+    }
+
+    return Ok(generation);
+}
+
+// Get the generation number of an ID. (Any unique prefix of the ID works.)
+pub fn resolve_id(prefix: &str) -> Result<usize, io::Error> {
+    if prefix.is_empty() {
+        error!("Empty generation ID!");
+        return Err(custom_error("Empty generation ID!"));
+    }
+
+    let mut matches: Vec<usize> = Vec::new();
+
+    for i in list_gen_nums()? {
+        if let Some(m) = meta::read(i) {
+            if m.id.is_empty() == false && m.id.starts_with(prefix) {
+                matches.push(i);
+            }
+        }
+    }
+
+    matches.sort();
+
+    match matches.len() {
+        1 => Ok(matches[0]),
+        0 => {
+            error!("Invalid generation reference: '{prefix}' (Expected a number, an ID, 'current', 'built' or 'latest'.)");
+            Err(custom_error("Invalid generation reference!"))
+        },
+        _ => {
+            error!("Generation ID '{prefix}' is ambiguous! (Matches generations: {})", matches.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", "));
+            Err(custom_error("Ambiguous generation ID!"))
+        },
+    }
+}
+
+// Get the generation and commit message a diff reference points to. ('user' is the uncommitted user generation, 'git:REV' is a revision of the git history.)
+pub fn resolve_diff_ref(reference: &str) -> Result<(Generation, String), io::Error> {
+    if reference == "user" {
+        return Ok((gen(ConfigSide::User)?, String::from("<< USER CONFIG (UNCOMMITTED) >>")));
+    }
+
+    if let Some(rev) = reference.strip_prefix("git:") {
+        return git::read(rev);
+    }
+
+    let generation = resolve_ref(reference)?;
+
+    let commit = get_gen_commit_from_usize(generation).unwrap_or(String::from("<< COMMIT MESSAGE MISSING >>"));
+
+    return Ok((get_gen_from_usize(generation)?, commit));
+}
+
 // Get latest generation number.
 pub fn latest_number() -> Result<usize, io::Error> {
     let generation_numbers = match list_gen_nums() {
@@ -350,22 +884,121 @@ pub fn latest_number() -> Result<usize, io::Error> {
     return Ok(latest_num);
 }
 
-// Create a new system generation based on the user generation.
-pub fn commit(msg: &str) -> Result<(), io::Error> {
+// Create a message that sums up the changes between two generations. (Example: "+3 system, -1 flatpak")
+pub fn auto_message(old: &Generation, new: &Generation) -> String {
+    let history = library::history_gen(old, new);
+
+    let mut managers: Vec<&String> = history.keys().collect();
+
+    managers.sort();
+
+    let mut parts: Vec<String> = Vec::new();
+
+    for i in managers {
+        let changes = history.get(i).unwrap();
+
+        let added = changes.iter().filter(|x| x.mode == HistoryMode::Add).count();
+        let removed = changes.iter().filter(|x| x.mode == HistoryMode::Remove).count();
+
+        if added > 0 {
+            parts.push(format!("+{added} {i}"));
+        }
+
+        if removed > 0 {
+            parts.push(format!("-{removed} {i}"));
+        }
+    }
+
+    if parts.len() == 0 {
+        return String::from("No changes");
+    }
+
+    parts.join(", ")
+}
+
+// Let the user write the commit message in $EDITOR. (Starting with a suggested message.)
+fn edit_message(suggested: &str) -> Result<String, io::Error> {
+    let path = places::base().add_str("COMMIT_MSG");
+
+    file::write(&format!("{suggested}\n\n# Enter the commit message above. Lines starting with '#' are ignored.\n# An empty message aborts the commit.\n"), &path)?;
+
+    let editor = std::env::var("EDITOR").unwrap_or(String::from("vi"));
+    let mut editor_args = editor.split_whitespace();
+
+    let status = std::process::Command::new(editor_args.next().unwrap_or("vi"))
+        .args(editor_args)
+        .arg(path.to_string())
+        .status();
+
+    let written = file::read(&path);
+
+    fs_action::delete(&path)?;
+
+    match status {
+        Ok(o) if o.success() => (),
+        _ => {
+            error!("Failed to run editor: {editor}");
+            return Err(custom_error("Failed to run editor!"));
+        },
+    };
+
+    let msg = written?.lines()
+        .filter(|x| x.starts_with('#') == false)
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string();
+
+    if msg.is_empty() {
+        error!("Empty commit message! (Aborting commit.)");
+        return Err(custom_error("Empty commit message!"));
+    }
+
+    Ok(msg)
+}
+
+// Create a new system generation based on the user generation. (Returns the commit message.)
+// Without a message, one is generated from the changes since the latest generation. (Or written in $EDITOR if 'edit' is on.)
+pub fn commit(msg: Option<&str>, edit: bool, allow_empty: bool) -> Result<String, io::Error> {
     abort_if_locked();
 
-    let generation_number = match latest_number() {
+    let latest = match latest_number() {
         Ok(o) => o,
         Err(e) => return Err(e),
-    } + 1;
+    };
+
+    let generation_number = latest + 1;
 
     let gen_dir = places::gens().add_str(&generation_number.to_string());
 
-    let user_gen = match gen(ConfigSide::User) {
+    let settings = crate::settings::load()?;
+
+    let mut user_gen = match gen(ConfigSide::User) {
         Ok(o) => o,
         Err(e) => return Err(e),
     };
 
+    user_gen.normalize(settings.commit.sort_items);
+
+    let latest_gen = match latest > 0 && gen_exists(latest) {
+        true => get_gen_from_usize(latest)?,
+        false => Generation::default(),
+    };
+
+    if latest > 0 && latest_gen == user_gen && allow_empty == false {
+        error!("Nothing changed since generation {latest}! (Use '--allow-empty' to commit anyway.)");
+        return Err(custom_error("Nothing to commit!"));
+    }
+
+    let msg = match msg {
+        Some(s) => s.to_string(),
+        None => match edit {
+            true => edit_message(&auto_message(&latest_gen, &user_gen))?,
+            false => auto_message(&latest_gen, &user_gen),
+        },
+    };
+    let msg = msg.as_str();
+
     let user_gen_string = match toml::to_string(&user_gen) {
         Ok(o) => o,
         Err(_e) => {
@@ -382,9 +1015,25 @@ pub fn commit(msg: &str) -> Result<(), io::Error> {
         },
     };
 
+    let parent = match places::gens().add_str("current").exists() {
+        true => get_current().ok(),
+        false => None,
+    };
+
+    let gen_meta = GenMeta::new(&user_gen_string, parent, crate::profile::active())?;
+
+    let gen_meta_string = match toml::to_string(&gen_meta) {
+        Ok(o) => o,
+        Err(_e) => {
+            error!("Failed to convert generation metadata to string!");
+            return Err(custom_error("Failed to convert generation metadata to string!"));
+        },
+    };
+
     let files = vec![
         (msg, gen_dir.add_str("commit")),
         (user_gen_string.as_str(), gen_dir.add_str("gen.toml")),
+        (gen_meta_string.as_str(), gen_dir.add_str("meta.toml")),
     ];
 
     for i in files.iter() {
@@ -406,12 +1055,50 @@ pub fn commit(msg: &str) -> Result<(), io::Error> {
         };
     }
 
+    match snapshot::take(&gen_dir, &user_gen) {
+        Ok(_) => info!("Saved snapshot of managers and hooks."),
+        Err(e) => {
+            error!("Failed to save snapshot of managers and hooks!");
+
+            match fs_action::delete(&gen_dir) {
+                Ok(_) => (),
+                Err(e) => {
+                    error!("Failed to delete generation directory!");
+                    return Err(e);
+                },
+            };
+
+            return Err(e);
+        },
+    };
+
     match set_current(generation_number, true) {
         Ok(_o) => {},
         Err(e) => return Err(e),
     };
 
-    return Ok(());
+    if settings.history.git {
+        match git::record(generation_number, &user_gen_string, msg) {
+            Ok(_) => (),
+            Err(_) => warning!("Failed to record generation {generation_number} in git history! (The generation was still committed.)"),
+        };
+    }
+
+    if settings.gc.auto {
+        let policy = gc::GcPolicy::from_settings(&settings.gc)?;
+
+        if policy.is_empty() {
+            warning!("Automatic garbage collection is on, but the [gc] section of settings.toml has no retention policy!");
+        }
+
+        else {
+            let deleted = gc::gc(&policy, false, true)?;
+
+            info!("Garbage collected {deleted} generations!");
+        }
+    }
+
+    return Ok(msg.to_string());
 }
 
 fn get_order(gen: &Generation) -> Result<Vec<String>, io::Error> {
@@ -474,10 +1161,74 @@ fn get_order(gen: &Generation) -> Result<Vec<String>, io::Error> {
     Ok(return_order)
 }
 
-// Build the 'current' system generation.
-pub fn build() -> Result<(), io::Error> {
+// Commit and build the user generation. If the build fails, 'current' goes back to the built generation. (Returns the commit message.)
+pub fn switch(msg: Option<&str>, edit: bool, allow_empty: bool, keep_failed: bool, source: crate::cli::ManagerSource) -> Result<String, io::Error> {
     abort_if_locked();
 
+    // Where to go back to if the build fails.
+    let previous = match been_built() {
+        true => Some(get_built()?),
+        false => match places::gens().add_str("current").exists() {
+            true => Some(get_current()?),
+            false => None,
+        },
+    };
+
+    let msg = commit(msg, edit, allow_empty)?;
+
+    let new_num = get_current()?;
+
+    info!("Building generation {new_num}...");
+
+    match build(source) {
+        Ok(_) => Ok(msg),
+        Err(e) => {
+            error!("Failed to build generation {new_num}!");
+
+            match previous {
+                Some(p) => {
+                    set_current(p, true)?;
+
+                    if keep_failed {
+                        note!("Kept the failed generation: {new_num}");
+                    }
+
+                    else {
+                        delete(new_num, true)?;
+                    }
+                },
+                None => warning!("There is no previous generation to go back to! (Keeping generation {new_num} as 'current'.)"),
+            };
+
+            Err(e)
+        },
+    }
+}
+
+// Build the 'current' system generation. ('source' decides which managers remove the items of the built generation.)
+pub fn build(source: crate::cli::ManagerSource) -> Result<(), io::Error> {
+    abort_if_locked();
+
+    let from = get_built_no_output().ok();
+    let to = get_current().ok();
+
+    match build_core(source) {
+        Ok(summary) => {
+            journal::record(journal::EventKind::Build, from, to, journal::Outcome::Success, journal::counts(&summary));
+
+            Ok(())
+        },
+        Err(e) => {
+            journal::record(journal::EventKind::Build, from, to, journal::Outcome::Failure, BTreeMap::new());
+
+            Err(e)
+        },
+    }
+}
+
+// Build the 'current' system generation. (CORE | Returns the summary of changes.)
+fn build_core(source: crate::cli::ManagerSource) -> Result<HashMap<String, Vec<History>>, io::Error> {
+
     run_hook_and_return_if_err!("pre_build");
 
     let current_num = match get_current() {
@@ -490,11 +1241,12 @@ pub fn build() -> Result<(), io::Error> {
         Err(e) => return Err(e),
     };
 
+    let mut summary_entries: HashMap<String, Vec<History>> = HashMap::new();
+
     match file::read(&places::gens().add_str("built")) {
         Ok(o) => {
             let built_gen = read_to_gen(&places::gens().add_str(o.trim()).add_str("gen.toml"))?;
-
-            let mut summary_entries: HashMap<String, Vec<History>> = HashMap::new();
+            let built_num = get_built()?;
 
             let curr_order: Vec<String> = get_order(&curr_gen)?;
 
@@ -519,7 +1271,10 @@ pub fn build() -> Result<(), io::Error> {
                         }
 
                         man.add(&to_install)?;
-                        man.remove(&to_remove)?;
+
+                        if to_remove.len() > 0 {
+                            snapshot::removal_manager(i, built_num, source)?.remove(&to_remove)?;
+                        }
 
                         summary_entries.insert(i.to_string(), diffs);
                     },
@@ -543,7 +1298,7 @@ pub fn build() -> Result<(), io::Error> {
                 match curr_gen.managers.get(i) {
                     Some(_) => (),
                     None => {
-                        let man = load_manager(i)?;
+                        let man = snapshot::removal_manager(i, built_num, source)?;
 
                         man.remove(&built_items.items)?;
 
@@ -579,6 +1334,11 @@ pub fn build() -> Result<(), io::Error> {
                 let man = load_manager(i)?;
 
                 man.add(&curr_items.items)?;
+
+                summary_entries.insert(i.to_string(), curr_items.items.iter().map(|x| History {
+                    mode: HistoryMode::Add,
+                    line: x.to_string(),
+                }).collect());
             }
 
             note!("There is no summary. (First time building.)");
@@ -590,9 +1350,11 @@ pub fn build() -> Result<(), io::Error> {
         Err(e) => return Err(e),
     };
 
+    gc::mark_built(current_num)?;
+
     run_hook_and_return_if_err!("post_build");
 
-    Ok(())
+    Ok(summary_entries)
 }
 
 // Set the 'current' generation to another older generation.
@@ -606,9 +1368,13 @@ pub fn rollback(by: isize, verbose: bool) -> Result<(), io::Error> {
 
     let new_current = (current_num as isize) - by;
 
-    match set_current(new_current as usize, verbose) {
-        Ok(_o) => {},
-        Err(e) => return Err(e),
+    match write_current(new_current as usize, verbose) {
+        Ok(_o) => journal::record(journal::EventKind::Rollback, Some(current_num), Some(new_current as usize), journal::Outcome::Success, BTreeMap::new()),
+        Err(e) => {
+            journal::record(journal::EventKind::Rollback, Some(current_num), Some(new_current.max(0) as usize), journal::Outcome::Failure, BTreeMap::new());
+
+            return Err(e);
+        },
     };
 
     return Ok(());
@@ -633,6 +1399,27 @@ pub fn latest(verbose: bool) -> Result<(), io::Error> {
 pub fn set_current(to: usize, verbose: bool) -> Result<(), io::Error> {
     abort_if_locked();
 
+    let from = match places::gens().add_str("current").exists() {
+        true => get_current().ok(),
+        false => None,
+    };
+
+    let result = write_current(to, verbose);
+
+    let outcome = match result {
+        Ok(_) => journal::Outcome::Success,
+        Err(_) => journal::Outcome::Failure,
+    };
+
+    journal::record(journal::EventKind::SetCurrent, from, Some(to), outcome, BTreeMap::new());
+
+    result
+}
+
+// Write the 'current' tracking file. (Without recording it in the journal.)
+fn write_current(to: usize, verbose: bool) -> Result<(), io::Error> {
+    abort_if_locked();
+
     if to > match latest_number() {
         Ok(o) => o,
         Err(e) => return Err(e),
@@ -806,6 +1593,11 @@ pub fn delete(generation: usize, verbose: bool) -> Result<(), io::Error> {
         }
     }
 
+    if is_pinned(generation) {
+        warning!("Could not delete generation {}, because it is pinned, and is protected! (Unpin it first.)", generation);
+        return Ok(());
+    }
+
     if match exists(generation) {
         Ok(o) => o,
         Err(e) => return Err(e),
@@ -816,11 +1608,17 @@ pub fn delete(generation: usize, verbose: bool) -> Result<(), io::Error> {
 
     match fs_action::delete(&places::gens().add_str(&generation.to_string())) {
         Ok(_) => {
+            journal::record(journal::EventKind::Delete, Some(generation), None, journal::Outcome::Success, BTreeMap::new());
+
             if verbose {
                 info!("Deleted generation: {}", generation);
             }
+
+            remap_parents(generation, None)?;
         },
         Err(e) => {
+            journal::record(journal::EventKind::Delete, Some(generation), None, journal::Outcome::Failure, BTreeMap::new());
+
             error!("Failed to delete generation: {}", generation);
             return Err(e);
         },
@@ -829,6 +1627,68 @@ pub fn delete(generation: usize, verbose: bool) -> Result<(), io::Error> {
     return Ok(());
 }
 
+// Pin a generation, protecting it from being deleted.
+pub fn pin(generation: usize, note: Option<&str>, verbose: bool) -> Result<(), io::Error> {
+    abort_if_locked();
+
+    if exists(generation)? == false {
+        error!("Generation {} does not exist!", generation);
+        return Err(custom_error("Generation does not exist!"));
+    }
+
+    // The pin lives inside of the generation directory, so it moves along with the generation.
+    match file::write(note.unwrap_or(""), &places::gens().add_str(&generation.to_string()).add_str("pinned")) {
+        Ok(_) => {
+            if verbose {
+                info!("Pinned generation: {}", generation);
+            }
+        },
+        Err(e) => {
+            error!("Failed to create/write 'pinned' file!");
+            return Err(e);
+        },
+    };
+
+    return Ok(());
+}
+
+// Unpin a generation.
+pub fn unpin(generation: usize, verbose: bool) -> Result<(), io::Error> {
+    abort_if_locked();
+
+    if is_pinned(generation) == false {
+        warning!("Generation {} is not pinned!", generation);
+        return Ok(());
+    }
+
+    match fs_action::delete(&places::gens().add_str(&generation.to_string()).add_str("pinned")) {
+        Ok(_) => {
+            if verbose {
+                info!("Unpinned generation: {}", generation);
+            }
+        },
+        Err(e) => {
+            error!("Failed to delete 'pinned' file!");
+            return Err(e);
+        },
+    };
+
+    return Ok(());
+}
+
+// Is the generation pinned?
+pub fn is_pinned(generation: usize) -> bool {
+    places::gens().add_str(&generation.to_string()).add_str("pinned").exists()
+}
+
+// Get the note of a pinned generation. (None if the generation isn't pinned.)
+pub fn get_pin_note(generation: usize) -> Option<String> {
+    match file::read(&places::gens().add_str(&generation.to_string()).add_str("pinned")) {
+        Ok(o) => Some(o.trim().to_string()),
+        Err(_) => None,
+    }
+}
+
 // Move a generation to another spot. (Number -> Number)
 pub fn move_gen(from: usize, to: usize, verbose: bool) -> Result<(), io::Error> {
     abort_if_locked();
@@ -845,8 +1705,14 @@ pub fn move_gen(from: usize, to: usize, verbose: bool) -> Result<(), io::Error>
         info!("Moved generation: {from} -> {to}");
     }
 
+    // 'to' was an empty slot, so anything still pointing at it refers to a deleted generation.
+    remap_parents(to, None)?;
+
+    // Keep the parents of other generations pointing at the moved generation.
+    remap_parents(from, Some(to))?;
+
     if current {
-        set_current(to, verbose)?;
+        write_current(to, verbose)?;
     }
 
     if built {
@@ -856,6 +1722,21 @@ pub fn move_gen(from: usize, to: usize, verbose: bool) -> Result<(), io::Error>
     return Ok(());
 }
 
+// Point every generation whose parent is 'old' at 'new' instead. (None forgets the parent.)
+fn remap_parents(old: usize, new: Option<usize>) -> Result<(), io::Error> {
+    for i in list_gen_nums()? {
+        if let Some(mut m) = meta::read(i) {
+            if m.parent == Some(old) {
+                m.parent = new;
+
+                meta::write(i, &m)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // See if a generation exists.
 pub fn exists(generation: usize) -> Result<bool, io::Error> {
     let gen_nums = match list_gen_nums() {
@@ -876,7 +1757,7 @@ pub fn list_gen_nums() -> Result<Vec<usize>, io::Error> {
     let mut gen_nums: Vec<usize> = Vec::new();
 
     for i in gen_list.iter() {
-        gen_nums.push(match usize_from_gen_name(i.0.as_str()) {
+        gen_nums.push(match usize_from_gen_name(i.name.as_str()) {
             Ok(o) => o,
             Err(e) => return Err(e),
         });
@@ -924,18 +1805,31 @@ pub fn is_built(generation: usize) -> Result<bool, io::Error> {
     }
 }
 
+// A generation, as listed by list().
+#[derive(Clone, Debug)]
+pub struct GenListEntry {
+    pub name: String,
+    pub commit: String,
+    pub current: bool,
+    pub built: bool,
+    /// The note of the pin (None if the generation isn't pinned)
+    pub pin: Option<String>,
+    /// None for older generations that don't have a meta.toml
+    pub meta: Option<GenMeta>,
+}
+
 // List all generations. (NORMAL)
-pub fn list() -> Result<Vec<(String, String, bool, bool)>, io::Error> {
+pub fn list() -> Result<Vec<GenListEntry>, io::Error> {
     return list_core(true);
 }
 
 // List all generations. (ISOLATED MODE | For avoiding errors with called un-needed functions!)
-pub fn list_with_no_calls() -> Result<Vec<(String, String, bool, bool)>, io::Error> {
+pub fn list_with_no_calls() -> Result<Vec<GenListEntry>, io::Error> {
     return list_core(false);
 }
 
 // List all generations. (CORE)
-fn list_core(calls: bool) -> Result<Vec<(String, String, bool, bool)>, io::Error> {
+fn list_core(calls: bool) -> Result<Vec<GenListEntry>, io::Error> {
     let gen_listed = match directory::list_items(&places::gens()) {
         Ok(o) => o,
         Err(e) => {
@@ -957,7 +1851,7 @@ fn list_core(calls: bool) -> Result<Vec<(String, String, bool, bool)>, io::Error
         };
     }
 
-    let mut gens_with_info: Vec<(String, String, bool, bool)> = Vec::new();
+    let mut gens_with_info: Vec<GenListEntry> = Vec::new();
 
     for i in generations.iter() {
         let generation_name = i.basename();
@@ -965,6 +1859,7 @@ fn list_core(calls: bool) -> Result<Vec<(String, String, bool, bool)>, io::Error
 
         let current_number: usize;
         let built_number: usize;
+        let gen_meta: Option<GenMeta>;
 
         if calls == true {
             current_number = match get_current() {
@@ -975,27 +1870,27 @@ fn list_core(calls: bool) -> Result<Vec<(String, String, bool, bool)>, io::Error
                 Ok(o) => o,
                 Err(_e) => 0,
             };
+            gen_meta = match generation_name.parse::<usize>() {
+                Ok(o) => meta::read(o),
+                Err(_e) => None,
+            };
         } else {
             current_number = 0;
             built_number = 0;
+            gen_meta = None;
         }
 
-        let is_current: bool;
-        let is_built: bool;
-
-        if generation_name == current_number.to_string() {
-            is_current = true;
-        } else {
-            is_current = false;
-        }
-
-        if generation_name == built_number.to_string() {
-            is_built = true;
-        } else {
-            is_built = false;
-        }
-
-        gens_with_info.push((generation_name, commit_msg, is_current, is_built));
+        gens_with_info.push(GenListEntry {
+            current: generation_name == current_number.to_string(),
+            built: generation_name == built_number.to_string(),
+            pin: match i.add_str("pinned").exists() {
+                true => Some(file::read(&i.add_str("pinned")).unwrap_or_default().trim().to_string()),
+                false => None,
+            },
+            name: generation_name,
+            commit: commit_msg,
+            meta: gen_meta,
+        });
     }
 
     return Ok(gens_with_info);
@@ -1016,45 +1911,150 @@ pub fn list_print() -> Result<(), io::Error> {
     let mut max_digits: usize = 0;
 
     if list_items_sorted.len() > 0 {
-        max_digits = list_items_sorted[list_items_sorted.len() - 1].0.to_string().trim().len();
+        max_digits = list_items_sorted[list_items_sorted.len() - 1].name.to_string().trim().len();
     }
 
     for i in list_items_sorted.iter() {
         let mut misc_text = String::new();
 
-        if i.2 {
+        if i.current {
             misc_text.push_str(format!(" {}{}{}", "[".bright_black().bold(), "CURRENT".bright_green().bold(), "]".bright_black().bold()).as_str());
         }
 
-        if i.3 {
+        if i.built {
             misc_text.push_str(format!(" {}{}{}", "[".bright_black().bold(), "BUILT".bright_yellow().bold(), "]".bright_black().bold()).as_str());
         }
 
+        if let Some(ref note) = i.pin {
+            misc_text.push_str(format!(" {}{}{}", "[".bright_black().bold(), "PINNED".bright_magenta().bold(), "]".bright_black().bold()).as_str());
+
+            if note.is_empty() == false {
+                misc_text.push_str(format!(" {}", note.bright_black()).as_str());
+            }
+        }
+
+        let date = match i.meta.as_ref().and_then(|x| x.date()) {
+            Some(s) => s,
+            None => String::from("????-??-?? ??:??"), // Older generations have no date.
+        };
+
+        let id = match i.meta.as_ref().and_then(|x| x.short_id()) {
+            Some(s) => s,
+            None => "-".repeat(meta::SHORT_ID_LEN), // Older generations have no ID.
+        };
+
         let mut tabbed = String::new();
 
-        for _j in 0..(max_digits - i.0.trim().len()) {
+        for _j in 0..(max_digits - i.name.trim().len()) {
             tabbed.push_str(" ");
         }
 
-        generic!("{}{} ... ({}){}", tabbed, i.0, i.1, misc_text);
+        generic!("{}{} ... {} {} ({}){}", tabbed, i.name, id.bright_yellow(), date.bright_black(), i.commit, misc_text);
+    }
+
+    return Ok(());
+}
+
+// Print the commit message, metadata and items of a stored generation.
+pub fn show(generation: usize, manager: Option<&str>, raw: bool) -> Result<(), io::Error> {
+    let gen_path = places::gens().add_str(&generation.to_string()).add_str("gen.toml");
+
+    if raw {
+        let gen_string = match file::read(&gen_path) {
+            Ok(o) => o,
+            Err(e) => {
+                error!("Failed to read generation {generation}!");
+                return Err(e);
+            },
+        };
+
+        print!("{gen_string}");
+
+        return Ok(());
+    }
+
+    let commit = get_gen_commit_from_usize(generation).unwrap_or(String::from("<< COMMIT MESSAGE MISSING >>"));
+
+    let generation_obj = get_gen_filtered(generation, manager)?;
+
+    let mut misc = String::new();
+
+    if is_current(generation)? {
+        misc.push_str(format!(" {}{}{}", "[".bright_black().bold(), "CURRENT".bright_green().bold(), "]".bright_black().bold()).as_str());
+    }
+
+    if been_built() && is_built(generation)? {
+        misc.push_str(format!(" {}{}{}", "[".bright_black().bold(), "BUILT".bright_yellow().bold(), "]".bright_black().bold()).as_str());
+    }
+
+    if is_pinned(generation) {
+        misc.push_str(format!(" {}{}{}", "[".bright_black().bold(), "PINNED".bright_magenta().bold(), "]".bright_black().bold()).as_str());
+    }
+
+    println!("{} {}{misc}", format!("Generation {generation}:").bold(), commit.bright_cyan().bold());
+
+    match meta::read(generation) {
+        Some(m) => {
+            if m.id.is_empty() == false {
+                generic!("ID: {}", m.id);
+            }
+
+            generic!("Date: {}", m.date().unwrap_or(String::from("????-??-?? ??:??")));
+            generic!("Host: {}", m.hostname);
+            generic!("User: {}", m.user);
+            generic!("Rebos version: {}", m.version);
+
+            if let Some(p) = m.parent {
+                generic!("Parent: {p}");
+            }
+
+            if let Some(p) = m.profile {
+                generic!("Profile: {p}");
+            }
+
+            generic!("Hash: {}", m.hash);
+        },
+        None => note!("Generation {generation} has no metadata. (It was committed by an older version of Rebos.)"),
+    };
+
+    if let Some(n) = get_pin_note(generation) {
+        generic!("Pin note: {n}");
     }
 
+    crate::obj_print::generation(&generation_obj);
+
     return Ok(());
 }
 
+// Get a stored generation, optionally with only the items of one manager.
+pub fn get_gen_filtered(generation: usize, manager: Option<&str>) -> Result<Generation, io::Error> {
+    let mut generation_obj = get_gen_from_usize(generation)?;
+
+    if let Some(m) = manager {
+        if generation_obj.managers.contains_key(m) == false {
+            error!("Generation {generation} has no items for manager '{m}'!");
+            return Err(custom_error("Manager not found in generation!"));
+        }
+
+        generation_obj.managers.retain(|k, _| k == m);
+    }
+
+    Ok(generation_obj)
+}
+
 // Get only list vector generation names.
-fn get_list_vector_names(list_vec: &Vec<(String, String, bool, bool)>) -> Vec<String> {
+fn get_list_vector_names(list_vec: &Vec<GenListEntry>) -> Vec<String> {
     let mut new_vec: Vec<String> = Vec::new();
 
     for i in list_vec.iter() {
-        new_vec.push(i.0.to_string());
+        new_vec.push(i.name.to_string());
     }
 
     return new_vec;
 }
 
 // Sort list vector.
-fn sort_list_vector(list_vec: &Vec<(String, String, bool, bool)>) -> Result<Vec<(String, String, bool, bool)>, io::Error> {
+fn sort_list_vector(list_vec: &Vec<GenListEntry>) -> Result<Vec<GenListEntry>, io::Error> {
     if list_vec.len() == 0 {
         return Ok(list_vec.clone());
     }
@@ -1072,11 +2072,11 @@ fn sort_list_vector(list_vec: &Vec<(String, String, bool, bool)>) -> Result<Vec<
 
     list_vec_nums.sort();
 
-    let mut new_vec: Vec<(String, String, bool, bool)> = Vec::new();
+    let mut new_vec: Vec<GenListEntry> = Vec::new();
 
     for i in list_vec_nums.iter() {
         for j in list_vec.iter() {
-            let j_num: usize = match usize_from_gen_name(j.0.as_str()) {
+            let j_num: usize = match usize_from_gen_name(j.name.as_str()) {
                 Ok(o) => o,
                 Err(e) => return Err(e),
             };
@@ -0,0 +1,65 @@
+use std::io;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::places;
+use crate::management::{ self, Manager };
+use crate::cli::ManagerSource;
+use super::Generation;
+
+// Where the managers and hooks of a generation are kept. (Same layout as the user configuration.)
+pub fn path(generation: usize) -> Path {
+    places::gens().add_str(&generation.to_string()).add_str("snapshot")
+}
+
+// Was the generation committed with a snapshot? (Older generations don't have one.)
+pub fn exists(generation: usize) -> bool {
+    path(generation).exists()
+}
+
+// Copy the managers and hooks used by a generation into its directory.
+pub fn take(gen_dir: &Path, gen: &Generation) -> Result<(), io::Error> {
+    let snapshot_dir = gen_dir.add_str("snapshot");
+
+    directory::create(&snapshot_dir.add_str("managers"))?;
+    directory::create(&snapshot_dir.add_str("hooks"))?;
+
+    let available = management::managers()?;
+
+    for man in gen.managers.keys() {
+        // Missing managers will fail at build time, so there is nothing to save here.
+        if available.contains(man) == false {
+            continue;
+        }
+
+        let man_path = crate::format::find(&places::base_user().add_str(&format!("managers/{man}")));
+
+        fs_action::copy(&man_path, &snapshot_dir.add_str("managers").add_str(&man_path.basename()))?;
+
+        let manager = management::load_manager_no_config_check(man)?;
+
+        for hook in manager.hook_names() {
+            let hook_path = places::base_user().add_str("hooks").add_str(&hook);
+
+            if hook_path.exists() {
+                fs_action::copy(&hook_path, &snapshot_dir.add_str("hooks").add_str(&hook))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Get the manager to remove items of a built generation with.
+pub fn removal_manager(man: &str, built: usize, source: ManagerSource) -> Result<Manager, io::Error> {
+    if source == ManagerSource::Snapshot {
+        if path(built).add_str("managers").exists() && crate::format::find(&path(built).add_str(&format!("managers/{man}"))).exists() {
+            return management::load_manager_from(man, &path(built));
+        }
+
+        note!("Generation {built} has no snapshot of manager '{man}'! (Using the live manager to remove items.)");
+    }
+
+    management::load_manager(man)
+}
@@ -4,7 +4,13 @@ macro_rules! run_hook_and_return_if_err {
     (
         $hook_name: expr
     ) => {
-        let macro_hook_path = crate::places::base_user().add_str("hooks").add_str(&format!("{}", $hook_name));
+        crate::hook::run_hook_and_return_if_err!($hook_name, crate::places::base_user().add_str("hooks"));
+    };
+    (
+        $hook_name: expr,
+        $hooks_dir: expr
+    ) => {
+        let macro_hook_path = $hooks_dir.add_str(&format!("{}", $hook_name));
 
         let hook_name = format!("{}", $hook_name);
 
@@ -25,4 +31,28 @@ macro_rules! run_hook_and_return_if_err {
     }
 }
 
+// Same as run_hook_and_return_if_err!(), but for shell snippets defined inside of a manager file.
+macro_rules! run_inline_hook_and_return_if_err {
+    (
+        $hook_name: expr,
+        $snippet: expr
+    ) => {
+        if let Some(ref macro_snippet) = $snippet {
+            let hook_name = format!("{}", $hook_name);
+
+            crate::info!("Running inline hook: {}", hook_name);
+
+            match library::run_command(macro_snippet) {
+                true => crate::info!("Successfully ran inline hook: {}", hook_name),
+                false => {
+                    crate::error!("Failed to run inline hook: {}", hook_name);
+
+                    return Err(library::custom_error("Failed to run inline hook!"));
+                },
+            };
+        }
+    }
+}
+
 pub(crate) use run_hook_and_return_if_err;
+pub(crate) use run_inline_hook_and_return_if_err;
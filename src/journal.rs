@@ -0,0 +1,224 @@
+#![allow(dead_code)]
+
+use std::io::{ self, Write };
+use std::collections::BTreeMap;
+use hashbrown::HashMap;
+use serde::{ Serialize, Deserialize };
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+use colored::Colorize;
+
+use crate::places;
+use crate::library::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Build,
+    Rollback,
+    SetCurrent,
+    Delete,
+    Align,
+    Upgrade,
+}
+
+impl EventKind {
+    fn name(&self) -> &'static str {
+        match *self {
+            Self::Build => "build",
+            Self::Rollback => "rollback",
+            Self::SetCurrent => "set-current",
+            Self::Delete => "delete",
+            Self::Align => "align",
+            Self::Upgrade => "upgrade",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Success,
+    Failure,
+}
+
+// How many items of a manager changed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Counts {
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Event {
+    /// When the event happened (RFC 3339)
+    pub timestamp: String,
+    pub kind: EventKind,
+    pub from: Option<usize>,
+    pub to: Option<usize>,
+    pub outcome: Outcome,
+    /// Manager name -> changed items (Only for builds)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub counts: BTreeMap<String, Counts>,
+}
+
+impl Event {
+    // The time of the event. (None if it is invalid.)
+    pub fn time(&self) -> Option<chrono::DateTime<chrono::Local>> {
+        match chrono::DateTime::parse_from_rfc3339(&self.timestamp) {
+            Ok(o) => Some(o.with_timezone(&chrono::Local)),
+            Err(_) => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Journal {
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+// The append-only event journal.
+pub fn path() -> Path {
+    places::base().add_str("journal.toml")
+}
+
+// Count the changes of a build summary.
+pub fn counts(summary: &HashMap<String, Vec<History>>) -> BTreeMap<String, Counts> {
+    summary.iter()
+        .filter(|(_, v)| v.len() > 0)
+        .map(|(k, v)| (k.to_string(), Counts {
+            added: v.iter().filter(|x| x.mode == HistoryMode::Add).count(),
+            removed: v.iter().filter(|x| x.mode == HistoryMode::Remove).count(),
+        }))
+        .collect()
+}
+
+// Add an event to the journal. (Failing to do so only warns, since the event already happened.)
+pub fn record(kind: EventKind, from: Option<usize>, to: Option<usize>, outcome: Outcome, counts: BTreeMap<String, Counts>) {
+    let event = Event {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        kind,
+        from,
+        to,
+        outcome,
+        counts,
+    };
+
+    if let Err(e) = append(event) {
+        warning!("Failed to write to the journal! ({e})");
+    }
+}
+
+fn append(event: Event) -> Result<(), io::Error> {
+    // Every event is its own [[events]] entry, so appending keeps the file valid.
+    let event_string = match toml::to_string(&Journal { events: vec![event] }) {
+        Ok(o) => o,
+        Err(_e) => return Err(custom_error("Failed to convert journal event to string!")),
+    };
+
+    let mut journal_file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path().to_string())?;
+
+    write!(journal_file, "\n{event_string}")?;
+
+    Ok(())
+}
+
+// Get every event in the journal. (Oldest first.)
+pub fn read() -> Result<Vec<Event>, io::Error> {
+    if path().exists() == false {
+        return Ok(Vec::new());
+    }
+
+    let journal_string = file::read(&path())?;
+
+    let journal: Journal = match toml::from_str(&journal_string) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to deserialize the journal: {e}");
+            return Err(custom_error("Failed to deserialize the journal!"));
+        },
+    };
+
+    Ok(journal.events)
+}
+
+// Parse the '--since' argument. (A date like '2024-01-31', or a duration like '7d'.)
+fn parse_since(since: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    if let Some(d) = crate::generation::gc::parse_duration(since) {
+        return Some(chrono::Local::now() - d);
+    }
+
+    let date = chrono::NaiveDate::parse_from_str(since.trim(), "%Y-%m-%d").ok()?;
+
+    date.and_hms_opt(0, 0, 0)?.and_local_timezone(chrono::Local).earliest()
+}
+
+// Get the events of the journal, optionally only the ones since a date or duration ago. (Oldest first.)
+pub fn events(since: Option<&str>) -> Result<Vec<Event>, io::Error> {
+    let since = match since {
+        Some(s) => match parse_since(s) {
+            Some(t) => Some(t),
+            None => {
+                error!("Invalid '--since' value: '{s}' (Examples: 2024-01-31, 12h, 7d)");
+                return Err(custom_error("Invalid '--since' value!"));
+            },
+        },
+        None => None,
+    };
+
+    let events: Vec<Event> = read()?.into_iter()
+        .filter(|x| match since {
+            Some(s) => x.time().map(|t| t >= s).unwrap_or(false),
+            None => true,
+        })
+        .collect();
+
+    Ok(events)
+}
+
+// Print the journal.
+pub fn print(since: Option<&str>) -> Result<(), io::Error> {
+    let events = events(since)?;
+
+    if events.len() == 0 {
+        info!("No events to show!");
+        return Ok(());
+    }
+
+    for e in events.iter() {
+        let date = e.time().map(|x| x.format("%Y-%m-%d %H:%M").to_string()).unwrap_or(String::from("????-??-?? ??:??"));
+
+        let generations = match (e.from, e.to) {
+            (Some(f), Some(t)) => format!("{f} -> {t}"),
+            (None, Some(t)) => format!("-> {t}"),
+            (Some(f), None) => format!("{f}"),
+            (None, None) => String::new(),
+        };
+
+        let outcome = match e.outcome {
+            Outcome::Success => "[SUCCESS]".bright_green().bold(),
+            Outcome::Failure => "[FAILURE]".bright_red().bold(),
+        };
+
+        let counts: Vec<String> = e.counts.iter()
+            .map(|(k, v)| format!("{k}: +{} -{}", v.added, v.removed))
+            .collect();
+
+        let counts = match counts.len() {
+            0 => String::new(),
+            _ => format!(" {}", format!("({})", counts.join(", ")).bright_black()),
+        };
+
+        generic!("{} {} {} {}{}", date.bright_black(), e.kind.name().bright_cyan().bold(), generations, outcome, counts);
+    }
+
+    Ok(())
+}
@@ -103,6 +103,11 @@ pub fn remove_array_duplicates<T: Clone + PartialEq>(dup_vec: &[T]) -> Vec<T> {
     new_vec
 }
 
+// Do both arrays contain the same items? (Ignoring order and duplicates.)
+pub fn same_set<T: PartialEq>(array_1: &[T], array_2: &[T]) -> bool {
+    array_1.iter().all(|x| array_2.contains(x)) && array_2.iter().all(|x| array_1.contains(x))
+}
+
 pub fn history_gen(gen_1: &Generation, gen_2: &Generation) -> HashMap<String, Vec<History>> {
     let mut history_map: HashMap<String, Vec<History>> = HashMap::new();
 
@@ -134,7 +139,11 @@ pub fn history_gen(gen_1: &Generation, gen_2: &Generation) -> HashMap<String, Ve
 }
 
 pub fn print_history_gen(history: &HashMap<String, Vec<History>>) {
-    for i in history.keys() {
+    let mut managers: Vec<&String> = history.keys().collect();
+
+    managers.sort();
+
+    for i in managers {
         piglog::info!("{}:", i);
 
         print_history(history.get(i).unwrap());
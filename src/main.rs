@@ -12,6 +12,13 @@ mod system; // Used for getting system information.
 mod hook; // Hook stuff.
 mod lock; // Locking file functionality.
 mod proc; // Process management stuff for Rebos.
+mod profile; // Generation profiles.
+mod settings; // User settings for Rebos itself.
+mod format; // Configuration file formats. (TOML, YAML, JSON)
+mod edit; // Editing the user generation files.
+mod output; // Machine-readable output.
+mod store; // Exporting and importing the generation store.
+mod journal; // The event journal.
 
 // Import stuff from source files and crates.
 use clap::Parser;
@@ -106,6 +113,13 @@ fn app() -> ExitCode {
 
     let args = cli::Cli::parse();
 
+    let json = args.output == cli::OutputFormat::Json;
+
+    if json && args.command.supports_json() == false {
+        error!("This command does not support '--output json'!");
+        return ExitCode::Fail;
+    }
+
     match &args.command {
         cli::Commands::Setup => (),
         _ => {
@@ -126,18 +140,81 @@ fn app() -> ExitCode {
 
             match command {
                 cli::GenCommands::Commit(c) => {
+                    if let Some(ref profile) = c.profile {
+                        match profile::set_override(profile) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
                     info!("Committing user generation...");
 
-                    match generation::commit(c.msg.as_str()) {
-                        Ok(_) => success!("Committed generation successfully! (\"{}\")", c.msg),
+                    match generation::commit(c.msg.as_deref(), c.edit, c.allow_empty) {
+                        Ok(o) => success!("Committed generation successfully! (\"{}\")", o),
                         Err(_) => return ExitCode::Fail,
                     };
+
+                    if c.build {
+                        info!("Building 'current' generation...");
+
+                        match generation::build(cli::ManagerSource::Snapshot) {
+                            Ok(_) => success!("Built generation successfully!"),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
                 },
-                cli::GenCommands::List => {
-                    match generation::list_print() {
+                cli::GenCommands::Add(e) | cli::GenCommands::Rm(e) => {
+                    let adding = matches!(command, cli::GenCommands::Add(_));
+
+                    let result = match adding {
+                        true => edit::gen_add(&e.manager, &e.items, e.import.as_deref(), e.machine),
+                        false => edit::gen_rm(&e.manager, &e.items, e.import.as_deref(), e.machine),
+                    };
+
+                    match result {
                         Ok(_) => (),
                         Err(_) => return ExitCode::Fail,
                     };
+
+                    if e.commit || e.build {
+                        let msg = format!("{} {} ({})", if adding { "Add" } else { "Remove" }, e.items.join(", "), e.manager);
+
+                        info!("Committing user generation...");
+
+                        match generation::commit(Some(&msg), false, false) {
+                            Ok(_) => success!("Committed generation successfully! (\"{}\")", msg),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
+                    if e.build {
+                        info!("Building 'current' generation...");
+
+                        match generation::build(cli::ManagerSource::Snapshot) {
+                            Ok(_) => success!("Built generation successfully!"),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+                },
+                cli::GenCommands::List => {
+                    if json {
+                        let list = match generation::list() {
+                            Ok(o) => o,
+                            Err(_) => return ExitCode::Fail,
+                        };
+
+                        match output::print(&output::list(list)) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
+                    else {
+                        match generation::list_print() {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
                 },
                 cli::GenCommands::CleanDups => {
                     match generation::management::clean_dups(true) {
@@ -157,19 +234,59 @@ fn app() -> ExitCode {
                         Err(_) => return ExitCode::Fail,
                     };
                 },
-                cli::GenCommands::Info => {
-                    let generation = match generation::gen(ConfigSide::User) {
-                        Ok(o) => o,
-                        Err(_) => return ExitCode::Fail,
-                    };
+                cli::GenCommands::Info { explain } => {
+                    if *explain {
+                        let (generation, report) = match generation::gen_with_report(ConfigSide::User) {
+                            Ok(o) => o,
+                            Err(_) => return ExitCode::Fail,
+                        };
+
+                        if json {
+                            match output::print(&output::info(&generation, Some(&report))) {
+                                Ok(_) => (),
+                                Err(_) => return ExitCode::Fail,
+                            };
+                        }
+
+                        else {
+                            obj_print::generation_explained(&generation, &report);
+                        }
+                    }
+
+                    else {
+                        let generation = match generation::gen(ConfigSide::User) {
+                            Ok(o) => o,
+                            Err(_) => return ExitCode::Fail,
+                        };
+
+                        if json {
+                            match output::print(&output::info(&generation, None)) {
+                                Ok(_) => (),
+                                Err(_) => return ExitCode::Fail,
+                            };
+                        }
 
-                    obj_print::generation(&generation);
+                        else {
+                            obj_print::generation(&generation);
+                        }
+                    }
                 },
                 cli::GenCommands::Latest => {
-                    info!("Latest generation number is: {}", match generation::latest_number() {
+                    let latest = match generation::latest_number() {
                         Ok(o) => o,
                         Err(_) => return ExitCode::Fail,
-                    });
+                    };
+
+                    if json {
+                        match output::print(&output::Latest { latest }) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
+                    else {
+                        info!("Latest generation number is: {}", latest);
+                    }
                 },
                 cli::GenCommands::DeleteOld(h) => {
                     info!("Deleting old generations...");
@@ -180,43 +297,178 @@ fn app() -> ExitCode {
                     };
                 },
                 cli::GenCommands::Delete(g) => {
-                    match generation::delete(g.generation, true) {
+                    let generation = match generation::resolve_ref(&g.generation) {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
+
+                    match generation::delete(generation, true) {
                         Ok(_) => (), // Handled by delete().
                         Err(_) => return ExitCode::Fail,
                     };
                 },
-                cli::GenCommands::Diff { old, new } => {
-                    if generation::gen_exists(*old) == false || generation::gen_exists(*new) == false {
-                        fatal!("Generation not found!");
+                cli::GenCommands::Gc(g) => {
+                    let policy = match g.keep_last.is_some() || g.keep_within.is_some() || g.keep_built_history.is_some() {
+                        true => generation::gc::GcPolicy::new(g.keep_last, g.keep_within.as_deref(), g.keep_built_history),
+                        false => match settings::load() {
+                            Ok(o) => generation::gc::GcPolicy::from_settings(&o.gc),
+                            Err(e) => Err(e),
+                        },
+                    };
 
-                        return ExitCode::Fail;
+                    let policy = match policy {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
+
+                    match generation::gc::gc(&policy, g.dry_run, true) {
+                        Ok(o) => match g.dry_run {
+                            true => info!("Would delete {o} generations! (Dry run.)"),
+                            false => success!("Deleted {o} generations!"),
+                        },
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+                cli::GenCommands::Pin(p) => {
+                    let generation = match generation::resolve_ref(&p.generation) {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
+
+                    match generation::pin(generation, p.note.as_deref(), true) {
+                        Ok(_) => success!("Pinned generation {} successfully!", generation),
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+                cli::GenCommands::Unpin(u) => {
+                    let generation = match generation::resolve_ref(&u.generation) {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
+
+                    match generation::unpin(generation, true) {
+                        Ok(_) => (), // Handled by unpin().
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+                cli::GenCommands::Show(s) => {
+                    let generation = match generation::resolve_ref(&s.generation) {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
+
+                    if json {
+                        if s.raw {
+                            error!("'--raw' can't be used with '--output json'!");
+                            return ExitCode::Fail;
+                        }
+
+                        let gen = match generation::get_gen_filtered(generation, s.manager.as_deref()) {
+                            Ok(o) => o,
+                            Err(_) => return ExitCode::Fail,
+                        };
+
+                        let show = match output::show(generation, &gen) {
+                            Ok(o) => o,
+                            Err(_) => return ExitCode::Fail,
+                        };
+
+                        match output::print(&show) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
                     }
 
-                    let gen_1 = generation::get_gen_from_usize(*old).unwrap();
-                    let gen_2 = generation::get_gen_from_usize(*new).unwrap();
+                    else {
+                        match generation::show(generation, s.manager.as_deref(), s.raw) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+                },
+                cli::GenCommands::Diff { old, new } => {
+                    let (gen_1, commit_1) = match generation::resolve_diff_ref(old) {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
 
-                    let commit_1 = generation::get_gen_commit_from_usize(*old).unwrap();
-                    let commit_2 = generation::get_gen_commit_from_usize(*new).unwrap();
+                    let (gen_2, commit_2) = match generation::resolve_diff_ref(new) {
+                        Ok(o) => o,
+                        Err(_) => return ExitCode::Fail,
+                    };
 
                     let history = library::history_gen(&gen_1, &gen_2);
 
-                    println!(
-                        "\n{} {} {}",
-                        commit_1.bright_cyan().bold(),
-                        "->".bright_black().bold(),
-                        commit_2.bright_cyan().bold()
-                    );
+                    if json {
+                        let diff = output::diff(
+                            output::DiffSide { reference: old.to_string(), message: commit_1 },
+                            output::DiffSide { reference: new.to_string(), message: commit_2 },
+                            &history,
+                        );
+
+                        match output::print(&diff) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
+                    else {
+                        println!(
+                            "\n{} {} {}",
+                            commit_1.bright_cyan().bold(),
+                            "->".bright_black().bold(),
+                            commit_2.bright_cyan().bold()
+                        );
+
+                        println!("");
+
+                        library::print_history_gen(&history);
+                    }
+                },
+                cli::GenCommands::Bisect { command } => {
+                    match command {
+                        cli::BisectCommands::Start { good, bad } => {
+                            let good = match generation::resolve_ref(good) {
+                                Ok(o) => o,
+                                Err(_) => return ExitCode::Fail,
+                            };
 
-                    println!("");
+                            let bad = match generation::resolve_ref(bad) {
+                                Ok(o) => o,
+                                Err(_) => return ExitCode::Fail,
+                            };
 
-                    library::print_history_gen(&history);
+                            match generation::bisect::start(good, bad) {
+                                Ok(_) => (),
+                                Err(_) => return ExitCode::Fail,
+                            };
+                        },
+                        cli::BisectCommands::Good | cli::BisectCommands::Bad => {
+                            match generation::bisect::mark(matches!(command, cli::BisectCommands::Good)) {
+                                Ok(_) => (),
+                                Err(_) => return ExitCode::Fail,
+                            };
+                        },
+                        cli::BisectCommands::Run { script } => {
+                            match generation::bisect::run(script) {
+                                Ok(_) => (),
+                                Err(_) => return ExitCode::Fail,
+                            };
+                        },
+                        cli::BisectCommands::Reset => {
+                            match generation::bisect::reset() {
+                                Ok(_) => success!("Stopped bisecting!"),
+                                Err(_) => return ExitCode::Fail,
+                            };
+                        },
+                    };
                 },
                 cli::GenCommands::Current { command } => {
                     match command {
-                        cli::CurrentCommands::Build => {
+                        cli::CurrentCommands::Build(b) => {
                             info!("Building 'current' generation...");
 
-                            match generation::build() {
+                            match generation::build(b.managers) {
                                 Ok(_) => success!("Built generation successfully!"),
                                 Err(_) => return ExitCode::Fail,
                             };
@@ -238,10 +490,15 @@ fn app() -> ExitCode {
                             };
                         },
                         cli::CurrentCommands::Set(s) => {
-                            info!("Jumping to generation {}...", s.to);
+                            let to = match generation::resolve_ref(&s.to) {
+                                Ok(o) => o,
+                                Err(_) => return ExitCode::Fail,
+                            };
+
+                            info!("Jumping to generation {}...", to);
 
-                            match generation::set_current(s.to, true) {
-                                Ok(_) => success!("Jumped to generation {} successfully!", s.to),
+                            match generation::set_current(to, true) {
+                                Ok(_) => success!("Jumped to generation {} successfully!", to),
                                 Err(_) => return ExitCode::Fail,
                             };
                         },
@@ -286,13 +543,37 @@ fn app() -> ExitCode {
                         Err(_) => return ExitCode::Fail,
                     };
 
-                    match result {
-                        Ok(misc_info) => config::print_misc_info(&misc_info),
-                        Err((e, misc_info)) => {
-                            config::print_errors_and_misc_info(&e, &misc_info);
+                    if json {
+                        let check = match result {
+                            Ok(ref misc_info) => output::check(&[], &misc_info.warnings),
+                            Err((ref e, ref misc_info)) => output::check(e, &misc_info.warnings),
+                        };
+
+                        match output::print(&check) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
 
+                        if check.ok == false {
                             return ExitCode::Fail;
-                        },
+                        }
+                    }
+
+                    else {
+                        match result {
+                            Ok(misc_info) => config::print_misc_info(&misc_info),
+                            Err((e, misc_info)) => {
+                                config::print_errors_and_misc_info(&e, &misc_info);
+
+                                return ExitCode::Fail;
+                            },
+                        };
+                    }
+                },
+                cli::ConfigCommands::Schema { kind } => {
+                    match config::print_schema(*kind) {
+                        Ok(_) => (),
+                        Err(_) => return ExitCode::Fail,
                     };
                 },
             };
@@ -346,11 +627,153 @@ fn app() -> ExitCode {
             }
         },
         cli::Commands::IsUnlocked => {
+            if json {
+                match output::print(&output::Unlocked { unlocked: lock::is_lock_on() == false }) {
+                    Ok(_) => (),
+                    Err(_) => return ExitCode::Fail,
+                };
+            }
+
             match lock::is_lock_on() {
                 false => return ExitCode::Success,
                 true => return ExitCode::Fail,
             };
         },
+        cli::Commands::Profile { command } => {
+            match lock::lock_on() {
+                Ok(_) => (),
+                Err(_) => return ExitCode::Fail,
+            };
+
+            match command {
+                cli::ProfileCommands::List => {
+                    if json {
+                        let profiles = match profile::list() {
+                            Ok(o) => o,
+                            Err(_) => return ExitCode::Fail,
+                        };
+
+                        match output::print(&output::Profiles { profiles, active: profile::active() }) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
+                    else {
+                        match profile::list_print() {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+                },
+                cli::ProfileCommands::Current => {
+                    if json {
+                        match output::print(&output::ActiveProfile { active: profile::active() }) {
+                            Ok(_) => (),
+                            Err(_) => return ExitCode::Fail,
+                        };
+                    }
+
+                    else {
+                        match profile::active() {
+                            Some(s) => info!("Active profile is: {s}"),
+                            None => info!("No profile is active! (Using gen.toml.)"),
+                        };
+                    }
+                },
+                cli::ProfileCommands::Switch { name } => {
+                    match profile::switch(name) {
+                        Ok(_) => success!("Switched to profile '{name}'!"),
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+                cli::ProfileCommands::Reset => {
+                    match profile::reset() {
+                        Ok(_) => success!("Stopped using profiles! (Using gen.toml.)"),
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+            };
+
+            match lock::lock_off() {
+                Ok(_) => (),
+                Err(_) => return ExitCode::Fail,
+            };
+        },
+        cli::Commands::History { since } => {
+            if json {
+                let events = match journal::events(since.as_deref()) {
+                    Ok(o) => o,
+                    Err(_) => return ExitCode::Fail,
+                };
+
+                match output::print(&output::Journal { events }) {
+                    Ok(_) => (),
+                    Err(_) => return ExitCode::Fail,
+                };
+            }
+
+            else {
+                match journal::print(since.as_deref()) {
+                    Ok(_) => (),
+                    Err(_) => return ExitCode::Fail,
+                };
+            }
+        },
+        cli::Commands::Switch(s) => {
+            match lock::lock_on() {
+                Ok(_) => (),
+                Err(_) => return ExitCode::Fail,
+            };
+
+            info!("Switching to the user generation...");
+
+            match generation::switch(s.msg.as_deref(), s.edit, s.allow_empty, s.keep_failed, s.managers) {
+                Ok(o) => success!("Switched successfully! (\"{}\")", o),
+                Err(_) => return ExitCode::Fail,
+            };
+
+            match lock::lock_off() {
+                Ok(_) => (),
+                Err(_) => return ExitCode::Fail,
+            };
+        },
+        cli::Commands::Store { command } => {
+            match lock::lock_on() {
+                Ok(_) => (),
+                Err(_) => return ExitCode::Fail,
+            };
+
+            match command {
+                cli::StoreCommands::Export { file, with_config } => {
+                    info!("Exporting generation store...");
+
+                    match store::export(file, *with_config) {
+                        Ok(o) => success!("Exported {o} generations to: {file}"),
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+                cli::StoreCommands::Import { file, merge, replace, with_config } => {
+                    let mode = match (*merge, *replace) {
+                        (true, _) => store::ImportMode::Merge,
+                        (_, true) => store::ImportMode::Replace,
+                        _ => store::ImportMode::Empty,
+                    };
+
+                    info!("Importing generation store...");
+
+                    match store::import(file, mode, *with_config) {
+                        Ok(o) => success!("Imported {o} generations!"),
+                        Err(_) => return ExitCode::Fail,
+                    };
+                },
+            };
+
+            match lock::lock_off() {
+                Ok(_) => (),
+                Err(_) => return ExitCode::Fail,
+            };
+        },
         cli::Commands::Managers { command } => {
             match command {
                 cli::ManagerCommands::Sync => {
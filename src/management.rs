@@ -2,6 +2,7 @@
 
 use std::io;
 use serde::Deserialize;
+use schemars::JsonSchema;
 use piglog::prelude::*;
 use piglog::*;
 use fspp::*;
@@ -10,10 +11,12 @@ use colored::Colorize;
 use crate::library::{ self, * };
 use crate::places;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields, default)]
 pub struct ManagerConfig {
+    /// Can many items be supplied as arguments at once?
     pub many_args: bool,
+    /// What to put between items when 'many_args' is on
     pub arg_sep: String,
 }
 
@@ -26,19 +29,81 @@ impl Default for ManagerConfig {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(deny_unknown_fields, default)]
+/// Shell snippets that run alongside the hook files
+pub struct ManagerHooks {
+    pub pre_add: Option<String>,
+    pub post_add: Option<String>,
+    pub pre_remove: Option<String>,
+    pub post_remove: Option<String>,
+    pub pre_sync: Option<String>,
+    pub post_sync: Option<String>,
+    pub pre_upgrade: Option<String>,
+    pub post_upgrade: Option<String>,
+}
+
+impl Default for ManagerHooks {
+    fn default() -> Self {
+        Self {
+            pre_add: None,
+            post_add: None,
+            pre_remove: None,
+            post_remove: None,
+            pre_sync: None,
+            post_sync: None,
+            pre_upgrade: None,
+            post_upgrade: None,
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
+/// A Rebos manager file (managers/NAME.toml)
 pub struct Manager {
+    /// Command to add items ('#:?' is replaced with the items)
     pub add: String,
+    /// Command to remove items ('#:?' is replaced with the items)
     pub remove: String,
+    /// Command to sync the manager
     pub sync: Option<String>,
+    /// Command to upgrade the manager
     pub upgrade: Option<String>,
     pub config: ManagerConfig,
+    /// Name used for hooks (Example: post_system_packages_add)
     pub hook_name: String,
+    /// Name used in messages (Example: system packages)
     pub plural_name: String,
+    #[serde(default)]
+    pub hooks: ManagerHooks,
+    // The directory the manager was loaded from. (Containing 'managers' and 'hooks'.)
+    #[serde(skip)]
+    pub base: Option<String>,
 }
 
 impl Manager {
+    // The directory that hook files are read from.
+    fn hooks_dir(&self) -> Path {
+        match self.base {
+            Some(ref s) => Path::new(s).add_str("hooks"),
+            None => places::base_user().add_str("hooks"),
+        }
+    }
+
+    // The names of every hook file this manager can run.
+    pub fn hook_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+
+        for pre in ["pre", "post"] {
+            for suf in ["add", "remove", "sync", "upgrade"] {
+                names.push(format!("{}_{}_{}", pre, self.hook_name, suf));
+            }
+        }
+
+        names
+    }
+
     fn join_args(&self, items: &[String]) -> String {
         items.join(&self.config.arg_sep)
     }
@@ -46,7 +111,8 @@ impl Manager {
     pub fn add(&self, items: &[String]) -> Result<(), io::Error> {
         let many = self.config.many_args;
 
-        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_add", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_add", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("pre_{}_add", self.hook_name), self.hooks.pre_add);
 
         if many {
             self.add_raw(&self.join_args(items))?;
@@ -58,7 +124,8 @@ impl Manager {
             }
         }
 
-        crate::hook::run_hook_and_return_if_err!(format!("post_{}_add", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("post_{}_add", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("post_{}_add", self.hook_name), self.hooks.post_add);
 
         Ok(())
     }
@@ -66,7 +133,8 @@ impl Manager {
     pub fn remove(&self, items: &[String]) -> Result<(), io::Error> {
         let many = self.config.many_args;
 
-        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_remove", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_remove", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("pre_{}_remove", self.hook_name), self.hooks.pre_remove);
 
         if many {
             self.remove_raw(&self.join_args(items))?;
@@ -78,7 +146,8 @@ impl Manager {
             }
         }
 
-        crate::hook::run_hook_and_return_if_err!(format!("post_{}_remove", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("post_{}_remove", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("post_{}_remove", self.hook_name), self.hooks.post_remove);
 
         Ok(())
     }
@@ -118,7 +187,8 @@ impl Manager {
     }
 
     pub fn sync(&self) -> Result<(), io::Error> {
-        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_sync", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_sync", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("pre_{}_sync", self.hook_name), self.hooks.pre_sync);
 
         if let Some(ref s) = self.sync {
             match run_command(s) {
@@ -131,13 +201,15 @@ impl Manager {
             };
         }
 
-        crate::hook::run_hook_and_return_if_err!(format!("post_{}_sync", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("post_{}_sync", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("post_{}_sync", self.hook_name), self.hooks.post_sync);
 
         Ok(())
     }
 
     pub fn upgrade(&self) -> Result<(), io::Error> {
-        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_upgrade", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("pre_{}_upgrade", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("pre_{}_upgrade", self.hook_name), self.hooks.pre_upgrade);
 
         if let Some(ref s) = self.upgrade {
             match run_command(s) {
@@ -150,7 +222,8 @@ impl Manager {
             };
         }
 
-        crate::hook::run_hook_and_return_if_err!(format!("post_{}_upgrade", self.hook_name));
+        crate::hook::run_hook_and_return_if_err!(format!("post_{}_upgrade", self.hook_name), self.hooks_dir());
+        crate::hook::run_inline_hook_and_return_if_err!(format!("post_{}_upgrade", self.hook_name), self.hooks.post_upgrade);
 
         Ok(())
     }
@@ -177,7 +250,14 @@ impl Manager {
 }
 
 pub fn load_manager_no_config_check(man: &str) -> Result<Manager, io::Error> {
-    let path = places::base_user().add_str(&format!("managers/{man}.toml"));
+    load_manager_no_config_check_from(man, &places::base_user())
+}
+
+// Load a manager from another directory than the user configuration. (Example: A generation snapshot.)
+pub fn load_manager_no_config_check_from(man: &str, base: &Path) -> Result<Manager, io::Error> {
+    let path = crate::format::find(&base.add_str(&format!("managers/{man}")));
+
+    let format = crate::format::Format::from_path(&path);
 
     let man_string = match file::read(&path) {
         Ok(o) => o,
@@ -189,21 +269,29 @@ pub fn load_manager_no_config_check(man: &str) -> Result<Manager, io::Error> {
         },
     };
 
-    let manager: Manager = match toml::from_str(&man_string) {
+    let mut manager: Manager = match format.deserialize(&man_string) {
         Ok(o) => o,
         Err(e) => {
             piglog::fatal!("Failed to deserialize manager! ({man})");
-            piglog::fatal!("Error: {e:#?}");
+            piglog::fatal!("Error: {e}");
 
             return Err(io::Error::new(io::ErrorKind::Other, "Failed to deserialize manager!"));
         },
     };
 
+    if base.to_string() != places::base_user().to_string() {
+        manager.base = Some(base.to_string());
+    }
+
     Ok(manager)
 }
 
 pub fn load_manager(man: &str) -> Result<Manager, io::Error> {
-    let manager = load_manager_no_config_check(man)?;
+    load_manager_from(man, &places::base_user())
+}
+
+pub fn load_manager_from(man: &str, base: &Path) -> Result<Manager, io::Error> {
+    let manager = load_manager_no_config_check_from(man, base)?;
 
     match manager.check_config() {
         Ok(_) => (),
@@ -226,10 +314,12 @@ pub fn managers() -> Result<Vec<String>, io::Error> {
 
     let man_list: Vec<String> = directory::list_items(&path)?
         .into_iter()
-        .map(|x| x.basename().replace(".toml", ""))
+        .map(|x| x.basename())
+        .filter(|x| crate::format::extension_of(x).is_some())
+        .map(|x| crate::format::strip_extension(&x))
         .collect();
 
-    Ok(man_list)
+    Ok(remove_array_duplicates(&man_list))
 }
 
 pub fn sync_all() -> Result<(), io::Error> {
@@ -250,6 +340,21 @@ pub fn sync_all() -> Result<(), io::Error> {
 }
 
 pub fn upgrade_all(sync_before_upgrade: bool) -> Result<(), io::Error> {
+    let built = crate::generation::get_built_no_output().ok();
+
+    let result = upgrade_all_core(sync_before_upgrade);
+
+    let outcome = match result {
+        Ok(_) => crate::journal::Outcome::Success,
+        Err(_) => crate::journal::Outcome::Failure,
+    };
+
+    crate::journal::record(crate::journal::EventKind::Upgrade, None, built, outcome, std::collections::BTreeMap::new());
+
+    result
+}
+
+fn upgrade_all_core(sync_before_upgrade: bool) -> Result<(), io::Error> {
     if sync_before_upgrade {
         sync_all()?;
     }
@@ -2,8 +2,11 @@
 
 use piglog::prelude::*;
 
-use crate::generation::Generation;
+use colored::Colorize;
+
+use crate::generation::{ Generation, GenReport };
 use crate::obj_print_boilerplate::macros::*;
+use crate::library::remove_array_duplicates;
 
 pub fn generation(gen: &Generation) {
     println!("");
@@ -12,3 +15,33 @@ pub fn generation(gen: &Generation) {
         print_entry!(i, gen.managers.get(i).unwrap().items);
     }
 }
+
+pub fn generation_explained(gen: &Generation, report: &GenReport) {
+    println!("");
+
+    for i in gen.managers.keys() {
+        piglog::info!("{}:", i);
+
+        for item in remove_array_duplicates(&gen.managers.get(i).unwrap().items) {
+            let sources = report.sources_of(i, &item);
+
+            piglog::generic!("{} {}", item, format!("({})", sources.join(", ")).bright_black());
+        }
+
+        println!("");
+    }
+
+    for i in gen.managers.keys() {
+        for item in remove_array_duplicates(&gen.managers.get(i).unwrap().items) {
+            let sources = report.sources_of(i, &item);
+
+            if sources.len() > 1 {
+                piglog::warning!("'{item}' ({i}) is declared in multiple places: {}", sources.join(", "));
+            }
+        }
+    }
+
+    for c in report.import_cycles.iter() {
+        piglog::warning!("Import cycle detected! ({})", c.join(" -> "));
+    }
+}
@@ -0,0 +1,231 @@
+// Machine-readable output. ('--output json')
+//
+// The structures in this file are the documented JSON format. Fields may be added in the
+// future, but existing fields will not be renamed or removed.
+
+use std::io;
+use std::collections::BTreeMap;
+use hashbrown::HashMap;
+use serde::Serialize;
+use piglog::prelude::*;
+use piglog::*;
+
+use crate::library::*;
+use crate::generation::{ Generation, GenReport, GenListEntry };
+use crate::generation::meta::GenMeta;
+use crate::journal::Event;
+use crate::config::{ ConfigError, ConfigWarning, ConfigInfoToMessage };
+
+/// An entry of 'gen list'.
+#[derive(Serialize, Debug)]
+pub struct ListEntry {
+    pub number: usize,
+    /// The commit message
+    pub message: String,
+    pub current: bool,
+    pub built: bool,
+    pub pinned: bool,
+    /// The note of the pin (null if there is no note, or the generation isn't pinned)
+    pub pin_note: Option<String>,
+    /// The contents of meta.toml (null for older generations that don't have one)
+    pub meta: Option<GenMeta>,
+}
+
+/// The output of 'gen info'.
+#[derive(Serialize, Debug)]
+pub struct Info {
+    /// Manager name -> items
+    pub managers: BTreeMap<String, Vec<String>>,
+    /// Manager name -> item -> files that declare the item (Only with '--explain')
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sources: Option<BTreeMap<String, BTreeMap<String, Vec<String>>>>,
+}
+
+/// The output of 'gen show'.
+#[derive(Serialize, Debug)]
+pub struct Show {
+    pub number: usize,
+    /// The commit message
+    pub message: String,
+    pub current: bool,
+    pub built: bool,
+    pub pinned: bool,
+    /// The note of the pin (null if there is no note, or the generation isn't pinned)
+    pub pin_note: Option<String>,
+    /// The contents of meta.toml (null for older generations that don't have one)
+    pub meta: Option<GenMeta>,
+    /// Manager name -> items (Only the manager given with '--manager')
+    pub managers: BTreeMap<String, Vec<String>>,
+}
+
+/// One side of 'gen diff'.
+#[derive(Serialize, Debug)]
+pub struct DiffSide {
+    /// The reference that was given on the command line (Example: 'current', 'user', '12')
+    pub reference: String,
+    /// The commit message (A placeholder for 'user')
+    pub message: String,
+}
+
+/// The changes of one manager in 'gen diff'.
+#[derive(Serialize, Debug)]
+pub struct ManagerDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The output of 'gen diff'.
+#[derive(Serialize, Debug)]
+pub struct Diff {
+    pub old: DiffSide,
+    pub new: DiffSide,
+    /// Manager name -> changes (Managers without changes are left out.)
+    pub managers: BTreeMap<String, ManagerDiff>,
+}
+
+/// The output of 'gen latest'.
+#[derive(Serialize, Debug)]
+pub struct Latest {
+    /// The latest generation number (0 if there are no generations)
+    pub latest: usize,
+}
+
+/// An error or warning of 'config check'.
+#[derive(Serialize, Debug)]
+pub struct CheckEntry {
+    /// Stable identifier (Example: 'missing_machine')
+    pub code: String,
+    pub message: String,
+}
+
+/// The output of 'config check'.
+#[derive(Serialize, Debug)]
+pub struct Check {
+    /// True if there are no errors (There can still be warnings.)
+    pub ok: bool,
+    pub errors: Vec<CheckEntry>,
+    pub warnings: Vec<CheckEntry>,
+}
+
+/// The output of 'history'.
+#[derive(Serialize, Debug)]
+pub struct Journal {
+    /// Oldest first
+    pub events: Vec<Event>,
+}
+
+/// The output of 'profile list'.
+#[derive(Serialize, Debug)]
+pub struct Profiles {
+    pub profiles: Vec<String>,
+    /// The active profile (null if gen.toml is used)
+    pub active: Option<String>,
+}
+
+/// The output of 'profile current'.
+#[derive(Serialize, Debug)]
+pub struct ActiveProfile {
+    /// The active profile (null if gen.toml is used)
+    pub active: Option<String>,
+}
+
+/// The output of 'is-unlocked'.
+#[derive(Serialize, Debug)]
+pub struct Unlocked {
+    pub unlocked: bool,
+}
+
+// Print a value as pretty JSON.
+pub fn print<T: Serialize>(value: &T) -> Result<(), io::Error> {
+    match serde_json::to_string_pretty(value) {
+        Ok(o) => println!("{o}"),
+        Err(e) => {
+            error!("Failed to convert output to JSON: {e}");
+            return Err(custom_error("Failed to convert output to JSON!"));
+        },
+    };
+
+    Ok(())
+}
+
+pub fn list(entries: Vec<GenListEntry>) -> Vec<ListEntry> {
+    let mut list: Vec<ListEntry> = entries.into_iter()
+        .filter_map(|x| match x.name.parse::<usize>() {
+            Ok(o) => Some(ListEntry {
+                number: o,
+                message: x.commit,
+                current: x.current,
+                built: x.built,
+                pinned: x.pin.is_some(),
+                pin_note: x.pin.filter(|x| x.is_empty() == false),
+                meta: x.meta,
+            }),
+            Err(_) => None,
+        })
+        .collect();
+
+    list.sort_by_key(|x| x.number);
+
+    list
+}
+
+pub fn info(gen: &Generation, report: Option<&GenReport>) -> Info {
+    let managers: BTreeMap<String, Vec<String>> = gen.managers.iter()
+        .map(|(k, v)| (k.to_string(), v.items.clone()))
+        .collect();
+
+    let sources = report.map(|r| {
+        managers.iter()
+            .map(|(man, items)| (man.to_string(), items.iter().map(|x| (x.to_string(), r.sources_of(man, x))).collect()))
+            .collect()
+    });
+
+    Info {
+        managers,
+        sources,
+    }
+}
+
+pub fn show(generation: usize, gen: &Generation) -> Result<Show, io::Error> {
+    let pin = crate::generation::get_pin_note(generation);
+
+    Ok(Show {
+        number: generation,
+        message: crate::generation::get_gen_commit_from_usize(generation).unwrap_or(String::from("<< COMMIT MESSAGE MISSING >>")),
+        current: crate::generation::is_current(generation)?,
+        built: crate::generation::been_built() && crate::generation::is_built(generation)?,
+        pinned: crate::generation::is_pinned(generation),
+        pin_note: pin.filter(|x| x.is_empty() == false),
+        meta: crate::generation::meta::read(generation),
+        managers: info(gen, None).managers,
+    })
+}
+
+pub fn diff(old: DiffSide, new: DiffSide, history: &HashMap<String, Vec<History>>) -> Diff {
+    let mut managers: BTreeMap<String, ManagerDiff> = BTreeMap::new();
+
+    for (man, changes) in history.iter() {
+        if changes.is_empty() {
+            continue;
+        }
+
+        managers.insert(man.to_string(), ManagerDiff {
+            added: changes.iter().filter(|x| x.mode == HistoryMode::Add).map(|x| x.line.to_string()).collect(),
+            removed: changes.iter().filter(|x| x.mode == HistoryMode::Remove).map(|x| x.line.to_string()).collect(),
+        });
+    }
+
+    Diff {
+        old,
+        new,
+        managers,
+    }
+}
+
+pub fn check(errors: &[ConfigError], warnings: &[ConfigWarning]) -> Check {
+    Check {
+        ok: errors.is_empty(),
+        errors: errors.iter().map(|x| CheckEntry { code: x.code().to_string(), message: x.msg() }).collect(),
+        warnings: warnings.iter().map(|x| CheckEntry { code: x.code().to_string(), message: x.msg() }).collect(),
+    }
+}
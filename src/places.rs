@@ -46,3 +46,8 @@ pub fn gens() -> Path {
 pub fn base_user() -> Path {
     location::config().unwrap().add_str("rebos")
 }
+
+/// The directory of the user's generation profiles
+pub fn profiles() -> Path {
+    base_user().add_str("profiles")
+}
@@ -0,0 +1,128 @@
+#![allow(dead_code)]
+
+use std::io;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::places;
+use crate::library::*;
+
+const OVERRIDE_ENV_NAME: &str = "__REBOS_PROFILE_OVERRIDE_THIS_ENV_VAR_SHOULD_NOT_BE_OVERWRITTEN__";
+
+// The file that keeps track of the active profile.
+fn active_file_path() -> Path {
+    places::base().add_str("profile")
+}
+
+// Path to the generation file of a profile.
+pub fn path_for(name: &str) -> Path {
+    crate::format::find(&places::profiles().add_str(name))
+}
+
+// Does the profile exist?
+pub fn exists(name: &str) -> bool {
+    path_for(name).exists()
+}
+
+// Get the active profile. (None means the plain 'gen.toml' is used.)
+pub fn active() -> Option<String> {
+    if let Ok(o) = std::env::var(OVERRIDE_ENV_NAME) {
+        return Some(o);
+    }
+
+    match file::read(&active_file_path()) {
+        Ok(o) => {
+            let name = o.trim().to_string();
+
+            if name.is_empty() {
+                return None;
+            }
+
+            Some(name)
+        },
+        Err(_) => None,
+    }
+}
+
+// Use a different profile for the rest of this Rebos process, without switching to it.
+pub fn set_override(name: &str) -> Result<(), io::Error> {
+    abort_if_missing(name)?;
+
+    std::env::set_var(OVERRIDE_ENV_NAME, name);
+
+    Ok(())
+}
+
+// Make a profile the active profile.
+pub fn switch(name: &str) -> Result<(), io::Error> {
+    abort_if_missing(name)?;
+
+    match file::write(name, &active_file_path()) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Failed to write active profile file!");
+            Err(e)
+        },
+    }
+}
+
+// Go back to using the plain 'gen.toml' file.
+pub fn reset() -> Result<(), io::Error> {
+    if active_file_path().exists() {
+        match fs_action::delete(&active_file_path()) {
+            Ok(_) => (),
+            Err(e) => {
+                error!("Failed to delete active profile file!");
+                return Err(e);
+            },
+        };
+    }
+
+    Ok(())
+}
+
+// List the names of all profiles.
+pub fn list() -> Result<Vec<String>, io::Error> {
+    if places::profiles().exists() == false {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles: Vec<String> = directory::list_items(&places::profiles())?
+        .into_iter()
+        .map(|x| x.basename())
+        .filter(|x| crate::format::extension_of(x).is_some())
+        .map(|x| crate::format::strip_extension(&x))
+        .collect();
+
+    profiles.sort();
+    profiles.dedup();
+
+    Ok(profiles)
+}
+
+// Print out the list of profiles.
+pub fn list_print() -> Result<(), io::Error> {
+    let active = active();
+
+    for p in list()? {
+        if Some(&p) == active.as_ref() {
+            generic!("{p} (active)");
+        }
+
+        else {
+            generic!("{p}");
+        }
+    }
+
+    Ok(())
+}
+
+fn abort_if_missing(name: &str) -> Result<(), io::Error> {
+    if exists(name) == false {
+        error!("Profile '{name}' does not exist! ({})", path_for(name).to_string());
+        return Err(custom_error("Profile does not exist!"));
+    }
+
+    Ok(())
+}
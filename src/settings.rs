@@ -0,0 +1,106 @@
+#![allow(dead_code)]
+
+use std::io;
+use serde::Deserialize;
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::places;
+use crate::library::*;
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct CommitSettings {
+    pub sort_items: bool,
+}
+
+impl Default for CommitSettings {
+    fn default() -> Self {
+        Self {
+            sort_items: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct GcSettings {
+    pub auto: bool,
+    pub keep_last: Option<usize>,
+    pub keep_within: Option<String>,
+    pub keep_built_history: Option<usize>,
+}
+
+impl Default for GcSettings {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            keep_last: None,
+            keep_within: None,
+            keep_built_history: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct HistorySettings {
+    pub git: bool,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            git: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields, default)]
+pub struct Settings {
+    pub commit: CommitSettings,
+    pub gc: GcSettings,
+    pub history: HistorySettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            commit: CommitSettings::default(),
+            gc: GcSettings::default(),
+            history: HistorySettings::default(),
+        }
+    }
+}
+
+// Path to the settings file.
+pub fn path() -> Path {
+    places::base_user().add_str("settings.toml")
+}
+
+// Load the settings file. (Defaults are used if the file doesn't exist.)
+pub fn load() -> Result<Settings, io::Error> {
+    if path().exists() == false {
+        return Ok(Settings::default());
+    }
+
+    let settings_string = match file::read(&path()) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to read settings file! ({})", path().to_string());
+            return Err(e);
+        },
+    };
+
+    match toml::from_str(&settings_string) {
+        Ok(o) => Ok(o),
+        Err(e) => {
+            error!("Failed to deserialize settings.toml!");
+            error!("TOML Error: {e:#?}");
+
+            Err(custom_error("Failed to deserialize settings.toml!"))
+        },
+    }
+}
@@ -0,0 +1,278 @@
+#![allow(dead_code)]
+
+use std::io;
+use std::fs::File;
+use serde::{ Serialize, Deserialize };
+use piglog::prelude::*;
+use piglog::*;
+use fspp::*;
+
+use crate::places;
+use crate::library::*;
+use crate::generation;
+
+// The version of the archive layout. (Bump when the layout changes!)
+const FORMAT: u32 = 1;
+
+// Describes the contents of an exported store. (manifest.toml)
+//
+// Archive layout:
+//   manifest.toml
+//   gens/<NUMBER>/...   (commit, gen.toml, meta.toml, ...)
+//   config/...          (The user configuration directory, only if exported with it.)
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StoreManifest {
+    pub format: u32,
+    /// Version of Rebos that exported the store
+    pub version: String,
+    /// When the store was exported (RFC 3339)
+    pub created: String,
+    pub generations: Vec<usize>,
+    pub current: Option<usize>,
+    pub built: Option<usize>,
+    /// Does the archive contain the user configuration?
+    pub config: bool,
+}
+
+// What to do with the generations that already exist when importing.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ImportMode {
+    /// Only import into an empty store
+    Empty,
+    /// Add the imported generations after the existing ones
+    Merge,
+    /// Delete the existing generations first
+    Replace,
+}
+
+// Bundle the generation store (and optionally the user configuration) into a tar archive.
+pub fn export(file: &str, with_config: bool) -> Result<usize, io::Error> {
+    crate::lock::abort_if_locked();
+
+    let archive_path = Path::new(file);
+
+    if archive_path.exists() {
+        error!("File already exists: {}", archive_path.to_string());
+        return Err(custom_error("File already exists!"));
+    }
+
+    let mut gen_nums = generation::list_gen_nums()?;
+    gen_nums.sort();
+
+    let manifest = StoreManifest {
+        format: FORMAT,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        created: chrono::Local::now().to_rfc3339(),
+        generations: gen_nums.clone(),
+        current: generation::get_current().ok(),
+        built: generation::get_built_no_output().ok(),
+        config: with_config,
+    };
+
+    let manifest_string = match toml::to_string(&manifest) {
+        Ok(o) => o,
+        Err(_e) => {
+            error!("Failed to convert store manifest to string!");
+            return Err(custom_error("Failed to convert store manifest to string!"));
+        },
+    };
+
+    let mut builder = tar::Builder::new(File::create(archive_path.to_string())?);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_string.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Local::now().timestamp() as u64);
+    header.set_cksum();
+
+    builder.append_data(&mut header, "manifest.toml", manifest_string.as_bytes())?;
+
+    for i in gen_nums.iter() {
+        builder.append_dir_all(format!("gens/{i}"), places::gens().add_str(&i.to_string()).to_string())?;
+    }
+
+    if with_config {
+        builder.append_dir_all("config", places::base_user().to_string())?;
+    }
+
+    builder.finish()?;
+
+    Ok(gen_nums.len())
+}
+
+// Restore generations (and optionally the user configuration) from an exported archive.
+pub fn import(file: &str, mode: ImportMode, with_config: bool) -> Result<usize, io::Error> {
+    crate::lock::abort_if_locked();
+
+    let archive_path = Path::new(file);
+
+    if archive_path.exists() == false {
+        error!("File not found: {}", archive_path.to_string());
+        return Err(custom_error("File not found!"));
+    }
+
+    let unpack_path = places::base().add_str("store_import");
+
+    if unpack_path.exists() {
+        fs_action::delete(&unpack_path)?;
+    }
+
+    directory::create(&unpack_path)?;
+
+    let result = import_unpacked(&archive_path, &unpack_path, mode, with_config);
+
+    fs_action::delete(&unpack_path)?;
+
+    result
+}
+
+fn import_unpacked(archive_path: &Path, unpack_path: &Path, mode: ImportMode, with_config: bool) -> Result<usize, io::Error> {
+    match tar::Archive::new(File::open(archive_path.to_string())?).unpack(unpack_path.to_string()) {
+        Ok(_) => (),
+        Err(e) => {
+            error!("Failed to unpack archive: {e}");
+            return Err(e);
+        },
+    };
+
+    let manifest = read_manifest(unpack_path)?;
+
+    // Check everything before touching the existing store.
+    for i in manifest.generations.iter() {
+        let gen_path = unpack_path.add_str(&format!("gens/{i}"));
+
+        if gen_path.add_str("commit").exists() == false {
+            error!("Generation {i} in the archive has no commit message!");
+            return Err(custom_error("Invalid archive!"));
+        }
+
+        // Same as reading a stored generation, so legacy generations are accepted.
+        if generation::read_to_gen(&gen_path.add_str("gen.toml")).is_err() {
+            error!("Generation {i} in the archive is invalid!");
+            return Err(custom_error("Invalid archive!"));
+        }
+    }
+
+    for (name, pointer) in [("current", manifest.current), ("built", manifest.built)] {
+        if let Some(p) = pointer {
+            if manifest.generations.contains(&p) == false {
+                error!("The '{name}' generation ({p}) of the archive is not in the archive!");
+                return Err(custom_error("Invalid archive!"));
+            }
+        }
+    }
+
+    if with_config {
+        if manifest.config == false {
+            error!("The archive does not contain the user configuration!");
+            return Err(custom_error("Archive has no configuration!"));
+        }
+
+        if places::base_user().exists() {
+            error!("User configuration already exists! Move it away first: {}", places::base_user().to_string());
+            return Err(custom_error("User configuration already exists!"));
+        }
+    }
+
+    let existing = generation::list_gen_nums()?;
+
+    if mode == ImportMode::Empty && existing.len() > 0 {
+        error!("The generation store is not empty! (Use '--merge' or '--replace'.)");
+        return Err(custom_error("The generation store is not empty!"));
+    }
+
+    if mode == ImportMode::Replace {
+        for i in existing.iter() {
+            fs_action::delete(&places::gens().add_str(&i.to_string()))?;
+        }
+
+        for i in ["current", "built"] {
+            if places::gens().add_str(i).exists() {
+                fs_action::delete(&places::gens().add_str(i))?;
+            }
+        }
+    }
+
+    // IDs that are already used by generations that stay in the store.
+    let existing_ids: Vec<String> = match mode {
+        ImportMode::Merge => existing.iter().filter_map(|x| generation::meta::read(*x)).map(|x| x.id).filter(|x| x.is_empty() == false).collect(),
+        _ => Vec::new(),
+    };
+
+    // Renumber on merge, so the imported generations come after the existing ones.
+    let offset = match mode {
+        ImportMode::Merge => generation::latest_number()?,
+        _ => 0,
+    };
+
+    let mut imported = manifest.generations.clone();
+    imported.sort();
+
+    for i in imported.iter() {
+        fs_action::mv(&unpack_path.add_str(&format!("gens/{i}")), &places::gens().add_str(&(i + offset).to_string()))?;
+
+        info!("Imported generation: {i} -> {}", i + offset);
+    }
+
+    if offset > 0 {
+        for i in imported.iter() {
+            if let Some(mut m) = generation::meta::read(i + offset) {
+                m.parent = m.parent.filter(|x| imported.contains(x)).map(|x| x + offset);
+
+                // IDs must stay unique. (Example: Importing the same archive twice.)
+                if existing_ids.contains(&m.id) {
+                    m.id = generation::meta::new_id();
+                }
+
+                generation::meta::write(i + offset, &m)?;
+            }
+        }
+    }
+
+    // Only take over the pointers if the store didn't have its own.
+    if places::gens().add_str("current").exists() == false {
+        if let Some(c) = manifest.current {
+            generation::set_current(c + offset, true)?;
+        }
+    }
+
+    if generation::been_built() == false {
+        if let Some(b) = manifest.built {
+            generation::set_built(b + offset, true)?;
+        }
+    }
+
+    if with_config {
+        fs_action::mv(&unpack_path.add_str("config"), &places::base_user())?;
+
+        info!("Restored user configuration: {}", places::base_user().to_string());
+    }
+
+    Ok(imported.len())
+}
+
+fn read_manifest(unpack_path: &Path) -> Result<StoreManifest, io::Error> {
+    let manifest_string = match file::read(&unpack_path.add_str("manifest.toml")) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Archive has no manifest.toml! (Was it exported by Rebos?)");
+            return Err(e);
+        },
+    };
+
+    let manifest: StoreManifest = match toml::from_str(&manifest_string) {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to deserialize archive manifest: {e}");
+            return Err(custom_error("Failed to deserialize archive manifest!"));
+        },
+    };
+
+    if manifest.format != FORMAT {
+        error!("Unsupported archive format: {} (Supported: {FORMAT})", manifest.format);
+        return Err(custom_error("Unsupported archive format!"));
+    }
+
+    Ok(manifest)
+}
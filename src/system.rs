@@ -21,3 +21,45 @@ pub fn hostname() -> Result<String, io::Error> {
         },
     });
 }
+
+// Get the distro ID from '/etc/os-release'. (Example: 'arch', 'debian', 'fedora')
+pub fn distro_id() -> Result<String, io::Error> {
+    let os_release = match std::fs::read_to_string("/etc/os-release") {
+        Ok(o) => o,
+        Err(e) => {
+            error!("Failed to read '/etc/os-release'!");
+            return Err(e);
+        },
+    };
+
+    for line in os_release.lines() {
+        if let Some(s) = line.trim().strip_prefix("ID=") {
+            return Ok(s.trim_matches('"').trim_matches('\'').to_string());
+        }
+    }
+
+    error!("Failed to find 'ID' field in '/etc/os-release'!");
+
+    Err(custom_error("Failed to get distro ID!"))
+}
+
+// Get the CPU architecture. (Example: 'x86_64', 'aarch64')
+pub fn arch() -> String {
+    std::env::consts::ARCH.to_string()
+}
+
+// Is the command available somewhere in $PATH?
+pub fn command_exists(command: &str) -> bool {
+    let path_var = match std::env::var_os("PATH") {
+        Some(s) => s,
+        None => return false,
+    };
+
+    for dir in std::env::split_paths(&path_var) {
+        if dir.join(command).is_file() {
+            return true;
+        }
+    }
+
+    false
+}